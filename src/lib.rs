@@ -62,11 +62,169 @@
 //! #     Ok(())
 //! # }
 //! ```
+//!
+//! # Enum tags
+//! Enums are encoded as a tag followed by the fields of the variant. The tag of a variant is its
+//! discriminant, so explicit discriminants keep the wire format stable when variants are reordered.
+//! Tags are a `u8` by default, and `#[wire(tag = u16)]` or `#[wire(tag = u32)]` widens them.
+//! ```
+//! use wire_framed::prelude::*;
+//!
+//! #[derive(Debug, Encoding, Decoding, PartialEq, Eq)]
+//! #[wire(tag = u16)]
+//! #[repr(u16)]
+//! pub enum Message {
+//!     Ping = 1,
+//!     Data(Vec<u8>) = 7,
+//!     Close = 1000,
+//! }
+//!
+//! assert_eq!(&Message::Close.into_frame()[..], &[0x03, 0xE8]);
+//! ```
+//!
+//! A variant marked `#[wire(other)]` is decoded for every unknown tag instead of failing. So that
+//! readers know where an unknown variant ends, an enum with a fallback writes the `u32` length of
+//! every variant's fields after its tag, and readers skip fields a newer writer appended to a known
+//! variant. The fallback can hold the unknown tag and the variant's bytes as `Bytes` (or `&[u8]` when
+//! borrowing), and encoding it writes both back unchanged.
+//! ```
+//! use wire_framed::prelude::*;
+//!
+//! #[derive(Debug, Encoding, Decoding, PartialEq, Eq)]
+//! pub enum Event {
+//!     Joined(u32),
+//!     #[wire(other)]
+//!     Unknown(u8, Bytes),
+//! }
+//!
+//! let event = Event::from_frame(Bytes::from_static(&[9, 0, 0, 0, 2, 1, 2])).unwrap();
+//! assert_eq!(event, Event::Unknown(9, Bytes::from_static(&[1, 2])));
+//! assert_eq!(&Event::Joined(7).into_frame()[..], &[0, 0, 0, 0, 4, 0, 0, 0, 7]);
+//! ```
+//!
+//! A fallback that does not hold both can only be decoded, since encoding it would lose the frame.
+//! ```compile_fail
+//! use wire_framed::prelude::*;
+//!
+//! #[derive(Encoding)]
+//! pub enum Event {
+//!     Joined(u32),
+//!     #[wire(other)]
+//!     Unknown,
+//! }
+//! ```
+//!
+//! Two variants with the same tag fail to compile.
+//! ```compile_fail
+//! use wire_framed::prelude::*;
+//!
+//! #[derive(Encoding)]
+//! pub enum Message {
+//!     Ping,
+//!     Pong = 0,
+//! }
+//! ```
+//!
+//! # Versioned structs
+//! A struct marked `#[wire(versioned)]` is prefixed with the `u32` length of its fields. Readers skip
+//! fields they don't know about, and fields marked `#[wire(since = N)]` decode as `Default::default()`
+//! when an older writer left them out. Fields with `since` have to come last, in version order.
+//! ```
+//! use wire_framed::prelude::*;
+//!
+//! #[derive(Debug, Encoding, Decoding, PartialEq, Eq)]
+//! #[wire(versioned)]
+//! pub struct User {
+//!     pub id: u32,
+//!     #[wire(since = 2)]
+//!     pub email: Option<String>,
+//! }
+//!
+//! let user = User::from_frame(Bytes::from_static(&[0, 0, 0, 4, 0, 0, 0, 7])).unwrap();
+//! assert_eq!(user, User { id: 7, email: None });
+//! ```
+//!
+//! # TLV structs
+//! A struct marked `#[wire(tlv)]` writes every field as its `u16` id from `#[wire(id = N)]`, a `u8` wire
+//! type and the `u32` length of its value, all behind the `u32` length of the struct. Fields can then be
+//! added, removed and reordered freely: readers skip unknown ids and decode missing fields as
+//! `Default::default()`. Ids must be unique and should never be reused for a different field.
+//! The wire type is `0` for the regular encoding, `1` for little-endian, `2` for varint and `3` for
+//! zigzag numbers, and `4` to `7` for `u8`, `u16`, `u64` and varint length prefixes. A field whose
+//! wire type does not match fails to decode.
+//! ```
+//! use wire_framed::prelude::*;
+//!
+//! #[derive(Debug, Encoding, Decoding, PartialEq, Eq)]
+//! #[wire(tlv)]
+//! pub struct User {
+//!     #[wire(id = 1)]
+//!     pub id: u32,
+//!     #[wire(id = 2)]
+//!     pub email: Option<String>,
+//! }
+//!
+//! // A frame holding only the `id` field and a field with the unknown id 9
+//! let frame = Bytes::from_static(&[0, 0, 0, 19, 0, 1, 0, 0, 0, 0, 4, 0, 0, 0, 7, 0, 9, 0, 0, 0, 0, 1, 42]);
+//! assert_eq!(User::from_frame(frame).unwrap(), User { id: 7, email: None });
+//! ```
+//!
+//! # Field attributes
+//! - `#[wire(skip)]` leaves the field out of the frame and decodes it as `Default::default()`.
+//! - `#[wire(default = "path")]` leaves the field out of the frame and decodes it by calling `path()`.
+//! - `#[wire(encode_with = "path")]` encodes the field with `fn(&T, &mut BytesMut)`.
+//! - `#[wire(decode_with = "path")]` decodes the field with `fn(&mut Bytes) -> Result<T, E>`, or
+//!   `fn(&mut &'a [u8]) -> Result<T, E>` for types that borrow from the frame.
+//! - `#[wire(with = "module")]` uses `module::encode` and `module::decode`.
+//! - `#[wire(le)]` and `#[wire(be)]` write a number in little- or big-endian byte order.
+//! - `#[wire(varint)]` writes an integer as a LEB128 varint, and `#[wire(zigzag)]` writes a signed
+//!   integer as a zigzag encoded varint so that small negative values stay short.
+//! - `#[wire(len = u8)]`, `u16`, `u32`, `u64` or `varint` sets the width of the length prefix of a
+//!   string, `Vec`, set or map field, which is a `u32` by default. A length that does not fit into its
+//!   prefix is never truncated: [`IntoFrame::try_extend_frame`], [`IntoFrame::try_into_frame`] and
+//!   [`TypedFrameCodec`] fail with an [`EncodeError`], while [`IntoFrame::extend_frame`] and
+//!   [`IntoFrame::into_frame`] panic.
+//!
+//! Numbers are big-endian by default. `#[wire(endian = "little")]` on a struct or enum makes its
+//! number fields and enum tags little-endian instead, unless a field says otherwise. It only applies
+//! to fields that are spelled as a primitive number type, so other fields that would still write
+//! big-endian numbers or length prefixes, such as strings, `Option`s or collections, fail to compile
+//! unless they have their own `encode_with` and `decode_with`. For the same reason it cannot be
+//! combined with `versioned` or `tlv`, or with a `#[wire(other)]` variant.
+//! ```
+//! use wire_framed::prelude::*;
+//!
+//! #[derive(Debug, Encoding, Decoding, PartialEq, Eq)]
+//! #[wire(endian = "little")]
+//! pub struct Reading {
+//!     pub sensor: u16,
+//!     #[wire(varint)]
+//!     pub serial: u64,
+//!     #[wire(zigzag)]
+//!     pub delta: i32,
+//! }
+//!
+//! let reading = Reading { sensor: 1, serial: 300, delta: -1 };
+//! assert_eq!(&reading.into_frame()[..], &[1, 0, 0xAC, 0x02, 1]);
+//! ```
+//!
+//! ```compile_fail
+//! use wire_framed::prelude::*;
+//!
+//! #[derive(Encoding)]
+//! #[wire(endian = "little")]
+//! pub struct Reading {
+//!     pub sensor: u16,
+//!     pub history: Vec<u16>,
+//! }
+//! ```
 
 
 pub use wire_framed_core::{
-    self, FromFrame, IntoFrame, FrameCodec, Framed, FramedRead, FramedWrite, bytes::{self, Bytes, BytesMut, Buf, BufMut}, codec, common_impls::*, utils
+    self, FromFrame, FromFrameRef, IntoFrame, BytesStr, Canonical, FrameCodec, FrameCodecBuilder, FrameTooLarge, LengthPrefix, ByteOrder, Framed, FramedRead, FramedWrite, TypedCodecError, TypedFrameCodec, TypedFramed, TypedFramedRead, TypedFramedWrite, DecodeError, DecodeErrorKind, DecodeLimits, EncodeError, PathSegment, bytes::{self, Bytes, BytesMut, Buf, BufMut}, codec, utils
 };
+#[allow(unused_imports)]
+pub use wire_framed_core::common_impls::*;
 pub use wire_framed_derive::{Decoding, Encoding};
 
 pub mod prelude {
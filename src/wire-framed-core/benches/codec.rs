@@ -0,0 +1,107 @@
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use wire_framed_core::codec::{Decoder, Encoder};
+use wire_framed_core::{utils, FrameCodec, FromFrame, IntoFrame};
+
+/// Size of a single read from the socket.
+const READ_SIZE: usize = 8 * 1024;
+
+/// The previous decoder, which assembled frames in its own buffer and copied them out.
+#[derive(Default)]
+struct CopyingCodec {
+    byte_count: Option<u32>,
+    data: BytesMut,
+}
+
+impl CopyingCodec {
+    fn decode(&mut self, src: &mut BytesMut) -> Option<Bytes> {
+        if self.byte_count.is_none() {
+            if src.len() < 4 {
+                return None;
+            }
+
+            let byte_count = src.get_u32();
+            self.data.reserve(byte_count as usize);
+            self.byte_count = Some(byte_count);
+        }
+
+        let byte_count = self.byte_count.unwrap();
+        let remaining_bytes = (byte_count - self.data.len() as u32) as usize;
+        let at = std::cmp::min(remaining_bytes, src.len());
+        self.data.put(src.split_to(at));
+
+        if byte_count == self.data.len() as u32 {
+            let frame = self.data.clone().freeze();
+            self.byte_count = None;
+            self.data.clear();
+            return Some(frame);
+        }
+
+        None
+    }
+}
+
+/// Feeds `wire` into the decoder in [`READ_SIZE`] chunks, like a socket read loop would.
+fn read_loop(wire: &[u8], mut decode: impl FnMut(&mut BytesMut) -> Option<Bytes>) -> usize {
+    let mut buf = BytesMut::with_capacity(READ_SIZE);
+    let mut decoded = 0;
+    for chunk in wire.chunks(READ_SIZE) {
+        buf.extend_from_slice(chunk);
+        while let Some(frame) = decode(&mut buf) {
+            decoded += frame.len();
+        }
+    }
+
+    decoded
+}
+
+fn decode_large_frames(c: &mut Criterion) {
+    let mut group = c.benchmark_group("decode");
+    for frame_size in [64 * 1024, 1024 * 1024, 4 * 1024 * 1024] {
+        let mut wire = BytesMut::new();
+        FrameCodec::new().encode(Bytes::from(vec![0xAB; frame_size]), &mut wire).unwrap();
+
+        group.throughput(Throughput::Bytes(frame_size as u64));
+        group.bench_with_input(BenchmarkId::new("zero_copy", frame_size), &wire, |b, wire| {
+            b.iter(|| {
+                let mut codec = FrameCodec::new();
+                read_loop(wire, |buf| codec.decode(buf).unwrap())
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("copying", frame_size), &wire, |b, wire| {
+            b.iter(|| {
+                let mut codec = CopyingCodec::default();
+                read_loop(wire, |buf| codec.decode(buf))
+            })
+        });
+    }
+
+    group.finish();
+}
+
+fn byte_buffers(c: &mut Criterion) {
+    let mut group = c.benchmark_group("blob");
+    for blob_size in [1024, 64 * 1024, 1024 * 1024] {
+        let blob = vec![0xABu8; blob_size];
+        let frame = blob.into_frame();
+
+        group.throughput(Throughput::Bytes(blob_size as u64));
+        group.bench_with_input(BenchmarkId::new("encode", blob_size), &blob, |b, blob| {
+            b.iter(|| blob.into_frame())
+        });
+        group.bench_with_input(BenchmarkId::new("decode_vec", blob_size), &frame, |b, frame| {
+            b.iter(|| Vec::<u8>::from_frame(frame.clone()).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("decode_bytes", blob_size), &frame, |b, frame| {
+            b.iter(|| Bytes::from_frame(frame.clone()).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("decode_per_byte", blob_size), &frame, |b, frame| {
+            b.iter(|| utils::get_array(&mut frame.clone(), "array", |frame| utils::get_u8(frame, "u8")).unwrap())
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, decode_large_frames, byte_buffers);
+criterion_main!(benches);
@@ -1,15 +1,176 @@
 use bytes::{Bytes, BytesMut, BufMut, Buf};
+use std::{fmt, io::{self, ErrorKind}, marker::PhantomData};
 pub use tokio_util::codec::{Decoder, Encoder};
 
+use crate::{utils::{put_varint_u64, varint_len_u64, MAX_VARINT_SIZE}, DecodeError, DecodeLimits, FromFrame, IntoFrame};
+
 pub type Framed<S> = tokio_util::codec::Framed<S, FrameCodec>;
 pub type FramedRead<S> = tokio_util::codec::FramedRead<S, FrameCodec>;
 pub type FramedWrite<S> = tokio_util::codec::FramedWrite<S, FrameCodec>;
 
+pub type TypedFramed<S, In, Out = In> = tokio_util::codec::Framed<S, TypedFrameCodec<In, Out>>;
+pub type TypedFramedRead<S, In, Out = In> = tokio_util::codec::FramedRead<S, TypedFrameCodec<In, Out>>;
+pub type TypedFramedWrite<S, In, Out = In> = tokio_util::codec::FramedWrite<S, TypedFrameCodec<In, Out>>;
+
+/// The default maximum frame length accepted by [`FrameCodec`] (8 MiB).
+pub const DEFAULT_MAX_FRAME_LENGTH: usize = 8 * 1024 * 1024;
+
+/// Error returned by [`FrameCodec`] when a frame exceeds the configured maximum length.
+///
+/// It is wrapped in an [`io::Error`] of kind [`ErrorKind::InvalidData`] and can be recovered
+/// with [`FrameTooLarge::from_io`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameTooLarge {
+    /// The length of the offending frame in bytes.
+    pub length: usize,
+    /// The maximum frame length the codec was configured with.
+    pub max_frame_length: usize,
+}
+
+impl FrameTooLarge {
+    /// Returns the [`FrameTooLarge`] error wrapped inside `err`, if any.
+    pub fn from_io(err: &io::Error) -> Option<&Self> {
+        err.get_ref().and_then(|inner| inner.downcast_ref::<Self>())
+    }
+}
+
+impl fmt::Display for FrameTooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "frame of {} bytes exceeds the maximum frame length of {} bytes", self.length, self.max_frame_length)
+    }
+}
+
+impl std::error::Error for FrameTooLarge {}
+
+impl From<FrameTooLarge> for io::Error {
+    fn from(err: FrameTooLarge) -> Self {
+        io::Error::new(ErrorKind::InvalidData, err)
+    }
+}
+
+/// Width of the length prefix written in front of every frame by [`FrameCodec`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LengthPrefix {
+    U8,
+    U16,
+    U32,
+    U64,
+    /// An unsigned LEB128 varint of 1 to 10 bytes. The byte order setting does not apply.
+    Varint,
+}
+
+impl LengthPrefix {
+    /// Returns the size of the length prefix in bytes, or the maximum size for [`LengthPrefix::Varint`].
+    pub fn size(&self) -> usize {
+        match self {
+            Self::U8 => 1,
+            Self::U16 => 2,
+            Self::U32 => 4,
+            Self::U64 => 8,
+            Self::Varint => MAX_VARINT_SIZE,
+        }
+    }
+
+    /// Returns the largest value that fits into the length prefix.
+    pub fn max_value(&self) -> u64 {
+        match self {
+            Self::U8 => u8::MAX as u64,
+            Self::U16 => u16::MAX as u64,
+            Self::U32 => u32::MAX as u64,
+            Self::U64 | Self::Varint => u64::MAX,
+        }
+    }
+}
+
+/// Byte order of the length prefix written by [`FrameCodec`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ByteOrder {
+    BigEndian,
+    LittleEndian,
+}
+
+/// Builder for a [`FrameCodec`] with a custom wire format.
+///
+/// The defaults match [`FrameCodec::new`]: a big-endian [`u32`] length prefix that does not
+/// count itself and a maximum frame length of [`DEFAULT_MAX_FRAME_LENGTH`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrameCodecBuilder {
+    length_prefix: LengthPrefix,
+    byte_order: ByteOrder,
+    length_includes_prefix: bool,
+    max_frame_length: usize,
+}
+
+impl FrameCodecBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the width of the length prefix.
+    pub fn length_prefix(mut self, length_prefix: LengthPrefix) -> Self {
+        self.length_prefix = length_prefix;
+        self
+    }
+
+    /// Sets the byte order of the length prefix.
+    pub fn byte_order(mut self, byte_order: ByteOrder) -> Self {
+        self.byte_order = byte_order;
+        self
+    }
+
+    /// Writes the length prefix in big-endian byte order.
+    pub fn big_endian(self) -> Self {
+        self.byte_order(ByteOrder::BigEndian)
+    }
+
+    /// Writes the length prefix in little-endian byte order.
+    pub fn little_endian(self) -> Self {
+        self.byte_order(ByteOrder::LittleEndian)
+    }
+
+    /// Sets whether the length prefix counts its own size in addition to the payload.
+    pub fn length_includes_prefix(mut self, length_includes_prefix: bool) -> Self {
+        self.length_includes_prefix = length_includes_prefix;
+        self
+    }
+
+    /// Sets the maximum frame length in bytes, excluding the length prefix.
+    pub fn max_frame_length(mut self, max_frame_length: usize) -> Self {
+        self.max_frame_length = max_frame_length;
+        self
+    }
+
+    /// Builds the configured [`FrameCodec`].
+    pub fn build(self) -> FrameCodec {
+        FrameCodec {
+            byte_count: None,
+            length_prefix: self.length_prefix,
+            byte_order: self.byte_order,
+            length_includes_prefix: self.length_includes_prefix,
+            max_frame_length: self.max_frame_length,
+        }
+    }
+}
+
+impl Default for FrameCodecBuilder {
+    fn default() -> Self {
+        Self {
+            length_prefix: LengthPrefix::U32,
+            byte_order: ByteOrder::BigEndian,
+            length_includes_prefix: false,
+            max_frame_length: DEFAULT_MAX_FRAME_LENGTH,
+        }
+    }
+}
+
 /// Codec type for [`Message`] that implements [`tokio_util::codec::Decoder`] and [`tokio_util::codec::Encoder`].
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FrameCodec {
-    byte_count: Option<u32>,
-    data: BytesMut,
+    byte_count: Option<usize>,
+    length_prefix: LengthPrefix,
+    byte_order: ByteOrder,
+    length_includes_prefix: bool,
+    max_frame_length: usize,
 }
 
 impl FrameCodec {
@@ -17,18 +178,114 @@ impl FrameCodec {
         Self::default()
     }
 
-    fn clear(&mut self) {
-        self.byte_count = None;
-        self.data.clear();
+    /// Returns a [`FrameCodecBuilder`] for configuring the wire format.
+    pub fn builder() -> FrameCodecBuilder {
+        FrameCodecBuilder::new()
     }
+
+    /// Sets the maximum frame length in bytes, excluding the length prefix.
+    ///
+    /// Frames larger than this are rejected with [`FrameTooLarge`] by both the encoder and
+    /// the decoder. The decoder checks the announced length before allocating anything.
+    pub fn with_max_frame_length(mut self, max_frame_length: usize) -> Self {
+        self.max_frame_length = max_frame_length;
+        self
+    }
+
+    /// Returns the maximum frame length in bytes.
+    pub fn max_frame_length(&self) -> usize {
+        self.max_frame_length
+    }
+
+    /// Returns the width of the length prefix.
+    pub fn length_prefix(&self) -> LengthPrefix {
+        self.length_prefix
+    }
+
+    /// Returns the byte order of the length prefix.
+    pub fn byte_order(&self) -> ByteOrder {
+        self.byte_order
+    }
+
+    /// Returns whether the length prefix counts its own size.
+    pub fn length_includes_prefix(&self) -> bool {
+        self.length_includes_prefix
+    }
+
+    fn check_frame_length(&self, length: usize) -> Result<(), FrameTooLarge> {
+        if length > self.max_frame_length {
+            return Err(FrameTooLarge { length, max_frame_length: self.max_frame_length });
+        }
+
+        Ok(())
+    }
+
+    fn put_length(&self, dst: &mut BytesMut, length: u64) {
+        match (self.length_prefix, self.byte_order) {
+            (LengthPrefix::U8, _) => dst.put_u8(length as u8),
+            (LengthPrefix::U16, ByteOrder::BigEndian) => dst.put_u16(length as u16),
+            (LengthPrefix::U16, ByteOrder::LittleEndian) => dst.put_u16_le(length as u16),
+            (LengthPrefix::U32, ByteOrder::BigEndian) => dst.put_u32(length as u32),
+            (LengthPrefix::U32, ByteOrder::LittleEndian) => dst.put_u32_le(length as u32),
+            (LengthPrefix::U64, ByteOrder::BigEndian) => dst.put_u64(length),
+            (LengthPrefix::U64, ByteOrder::LittleEndian) => dst.put_u64_le(length),
+            (LengthPrefix::Varint, _) => put_varint_u64(dst, length),
+        }
+    }
+
+    /// Reads the length prefix from the start of `src` without consuming it.
+    ///
+    /// Returns the decoded length and the size of the prefix, or `None` if `src` does not
+    /// contain the whole prefix yet.
+    fn peek_length(&self, src: &[u8]) -> Result<Option<(u64, usize)>, io::Error> {
+        if let LengthPrefix::Varint = self.length_prefix {
+            return peek_varint(src);
+        }
+
+        let prefix_size = self.length_prefix.size();
+        if src.len() < prefix_size {
+            return Ok(None);
+        }
+
+        let mut src = &src[..prefix_size];
+        let length = match (self.length_prefix, self.byte_order) {
+            (LengthPrefix::U8, _) => src.get_u8() as u64,
+            (LengthPrefix::U16, ByteOrder::BigEndian) => src.get_u16() as u64,
+            (LengthPrefix::U16, ByteOrder::LittleEndian) => src.get_u16_le() as u64,
+            (LengthPrefix::U32, ByteOrder::BigEndian) => src.get_u32() as u64,
+            (LengthPrefix::U32, ByteOrder::LittleEndian) => src.get_u32_le() as u64,
+            (LengthPrefix::U64, ByteOrder::BigEndian) => src.get_u64(),
+            (LengthPrefix::U64, ByteOrder::LittleEndian) => src.get_u64_le(),
+            (LengthPrefix::Varint, _) => unreachable!(),
+        };
+
+        Ok(Some((length, prefix_size)))
+    }
+
+    /// Computes the value of the length prefix and the size of the prefix for a payload of `length` bytes.
+    fn prefix_for(&self, length: usize) -> Option<(u64, usize)> {
+        let length = length as u64;
+        let (value, prefix_size) = match (self.length_prefix, self.length_includes_prefix) {
+            (LengthPrefix::Varint, false) => (length, varint_len_u64(length)),
+            (LengthPrefix::Varint, true) => {
+                // the prefix counts itself, so find the size at which it stops growing
+                (1..=MAX_VARINT_SIZE).find_map(|size| {
+                    let value = length.checked_add(size as u64)?;
+                    (varint_len_u64(value) == size).then_some((value, size))
+                })?
+            },
+            (prefix, false) => (length, prefix.size()),
+            (prefix, true) => (length.checked_add(prefix.size() as u64)?, prefix.size()),
+        };
+
+        (value <= self.length_prefix.max_value()).then_some((value, prefix_size))
+    }
+
 }
 
 impl Default for FrameCodec {
     fn default() -> Self {
-        Self {
-            byte_count: None,
-            data: BytesMut::new(),
-        }
+        FrameCodecBuilder::default().build()
     }
 }
 
@@ -36,9 +293,13 @@ impl Encoder<Bytes> for FrameCodec {
     type Error = std::io::Error;
 
     fn encode(&mut self, item: Bytes, dst: &mut BytesMut) -> Result<(), Self::Error> {
-        let byte_count = item.len() as u32;
-        dst.reserve(std::mem::size_of::<u32>() + byte_count as usize);
-        dst.put_u32(byte_count);
+        self.check_frame_length(item.len())?;
+
+        let (length, prefix_size) = self.prefix_for(item.len())
+            .ok_or_else(|| io::Error::new(ErrorKind::InvalidInput, "frame length does not fit into the length prefix"))?;
+
+        dst.reserve(prefix_size + item.len());
+        self.put_length(dst, length);
         dst.put(item);
 
         Ok(())
@@ -50,31 +311,464 @@ impl Decoder for FrameCodec {
     type Error = std::io::Error;
 
     fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
-        // read the initial frame length
-        if self.byte_count.is_none() {
-            if src.len() < std::mem::size_of::<u32>() {
-                return Ok(None);
+        // read the frame length, leaving partial prefixes in `src`
+        let byte_count = match self.byte_count {
+            Some(byte_count) => byte_count,
+            None => {
+                let (length, prefix_size) = match self.peek_length(src)? {
+                    Some(prefix) => prefix,
+                    None => return Ok(None),
+                };
+
+                let length = match self.length_includes_prefix {
+                    true => length.checked_sub(prefix_size as u64)
+                        .ok_or_else(|| io::Error::new(ErrorKind::InvalidData, "frame length is smaller than the length prefix"))?,
+                    false => length,
+                };
+                let byte_count = usize::try_from(length).unwrap_or(usize::MAX);
+                self.check_frame_length(byte_count)?;
+
+                src.advance(prefix_size);
+                self.byte_count = Some(byte_count);
+                byte_count
+            },
+        };
+
+        // wait for the rest of the frame, making room for it in the read buffer
+        if src.len() < byte_count {
+            src.reserve(byte_count - src.len());
+            return Ok(None);
+        }
+
+        // take the frame straight out of the read buffer without copying it
+        self.byte_count = None;
+        Ok(Some(src.split_to(byte_count).freeze()))
+    }
+}
+
+/// Error returned by [`TypedFrameCodec`].
+#[derive(Debug)]
+pub enum TypedCodecError<E> {
+    /// The underlying transport or the [`FrameCodec`] framing failed.
+    Io(io::Error),
+    /// A complete frame was received but could not be parsed into the target type.
+    Decode(E),
+}
+
+impl<E> From<io::Error> for TypedCodecError<E> {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for TypedCodecError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "frame io error: {}", err),
+            Self::Decode(err) => write!(f, "frame decode error: {}", err),
+        }
+    }
+}
+
+impl<E: std::error::Error + 'static> std::error::Error for TypedCodecError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            Self::Decode(err) => Some(err),
+        }
+    }
+}
+
+/// Codec that wraps a [`FrameCodec`] and yields [`FromFrame`] values instead of raw frames.
+///
+/// The decoder parses every frame into an `In` and the encoder accepts any `Out` that
+/// implements [`IntoFrame`].
+pub struct TypedFrameCodec<In, Out = In> {
+    codec: FrameCodec,
+    limits: DecodeLimits,
+    strict: bool,
+    _marker: PhantomData<fn(Out) -> In>,
+}
+
+impl<In, Out> TypedFrameCodec<In, Out> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Wraps an already configured [`FrameCodec`].
+    pub fn with_codec(codec: FrameCodec) -> Self {
+        Self {
+            codec,
+            limits: DecodeLimits::default(),
+            strict: false,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Sets whether frames with bytes left over after parsing are rejected.
+    ///
+    /// See [`FromFrame::from_frame_exact`].
+    pub fn with_strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Returns whether frames with bytes left over after parsing are rejected.
+    pub fn strict(&self) -> bool {
+        self.strict
+    }
+
+    /// Sets the [`DecodeLimits`] applied when parsing every frame.
+    pub fn with_limits(mut self, limits: DecodeLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Returns the [`DecodeLimits`] applied when parsing every frame.
+    pub fn limits(&self) -> DecodeLimits {
+        self.limits
+    }
+
+    /// Returns a reference to the underlying [`FrameCodec`].
+    pub fn codec(&self) -> &FrameCodec {
+        &self.codec
+    }
+
+    /// Consumes `self` and returns the underlying [`FrameCodec`].
+    pub fn into_codec(self) -> FrameCodec {
+        self.codec
+    }
+}
+
+impl<In, Out> Default for TypedFrameCodec<In, Out> {
+    fn default() -> Self {
+        Self::with_codec(FrameCodec::default())
+    }
+}
+
+impl<In, Out> Clone for TypedFrameCodec<In, Out> {
+    fn clone(&self) -> Self {
+        Self::with_codec(self.codec.clone())
+            .with_limits(self.limits)
+            .with_strict(self.strict)
+    }
+}
+
+impl<In, Out> fmt::Debug for TypedFrameCodec<In, Out> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TypedFrameCodec")
+            .field("codec", &self.codec)
+            .field("limits", &self.limits)
+            .field("strict", &self.strict)
+            .finish()
+    }
+}
+
+impl<In, Out> From<FrameCodec> for TypedFrameCodec<In, Out> {
+    fn from(codec: FrameCodec) -> Self {
+        Self::with_codec(codec)
+    }
+}
+
+impl<In, Out: IntoFrame> Encoder<Out> for TypedFrameCodec<In, Out> {
+    type Error = io::Error;
+
+    fn encode(&mut self, item: Out, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        self.codec.encode(item.try_into_frame()?, dst)
+    }
+}
+
+impl<In, Out> Decoder for TypedFrameCodec<In, Out>
+where
+    In: FromFrame,
+    In::Error: From<DecodeError>,
+{
+    type Item = In;
+    type Error = TypedCodecError<In::Error>;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        let frame = match self.codec.decode(src)? {
+            Some(frame) => frame,
+            None => return Ok(None),
+        };
+
+        let value = match self.strict {
+            true => In::from_frame_exact_with_limits(frame, self.limits),
+            false => In::from_frame_with_limits(frame, self.limits),
+        };
+        value.map(Some).map_err(TypedCodecError::Decode)
+    }
+}
+
+/// Reads an unsigned LEB128 varint from the start of `src` without consuming it.
+///
+/// Returns `None` if the varint is not complete yet. Varints longer than [`MAX_VARINT_SIZE`]
+/// bytes, varints that overflow a [`u64`] and non-minimal encodings are rejected.
+fn peek_varint(src: &[u8]) -> Result<Option<(u64, usize)>, io::Error> {
+    let mut value = 0u64;
+    for (i, byte) in src.iter().take(MAX_VARINT_SIZE).enumerate() {
+        let bits = (byte & 0x7F) as u64;
+        if i == MAX_VARINT_SIZE - 1 && *byte > 1 {
+            return Err(io::Error::new(ErrorKind::InvalidData, "varint length prefix overflows a u64"));
+        }
+
+        value |= bits << (7 * i);
+        if byte & 0x80 == 0 {
+            if i > 0 && *byte == 0 {
+                return Err(io::Error::new(ErrorKind::InvalidData, "overlong varint length prefix"));
             }
 
-            let byte_count = src.get_u32();
-            self.data.reserve(byte_count as usize);
-            self.byte_count = Some(byte_count);
+            return Ok(Some((value, i + 1)));
         }
+    }
+
+    if src.len() >= MAX_VARINT_SIZE {
+        return Err(io::Error::new(ErrorKind::InvalidData, "varint length prefix is longer than 10 bytes"));
+    }
+
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roundtrip_test() {
+        let mut codec = FrameCodec::new();
+        let mut buf = BytesMut::new();
+        codec.encode(Bytes::from_static(b"hello"), &mut buf).unwrap();
+        assert_eq!(&buf[..], &[0, 0, 0, 5, b'h', b'e', b'l', b'l', b'o']);
+
+        let frame = codec.decode(&mut buf).unwrap();
+        assert_eq!(frame, Some(Bytes::from_static(b"hello")));
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn decode_rejects_oversized_frame() {
+        let mut codec = FrameCodec::new().with_max_frame_length(16);
+        let mut buf = BytesMut::from(&[0xFF, 0xFF, 0xFF, 0xFF][..]);
 
-        // read chunk of data
-        let byte_count = self.byte_count.unwrap();
-        let remaining_bytes = (byte_count - self.data.len() as u32) as usize;
-        let at = std::cmp::min(remaining_bytes, src.len());
-        self.data.put(src.split_to(at));
+        let err = codec.decode(&mut buf).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert_eq!(FrameTooLarge::from_io(&err), Some(&FrameTooLarge { length: u32::MAX as usize, max_frame_length: 16 }));
+        assert!(buf.capacity() < 1024);
+    }
+
+    #[test]
+    fn encode_rejects_oversized_frame() {
+        let mut codec = FrameCodec::new().with_max_frame_length(4);
+        let mut buf = BytesMut::new();
+
+        let err = codec.encode(Bytes::from_static(b"hello"), &mut buf).unwrap_err();
+        assert_eq!(FrameTooLarge::from_io(&err), Some(&FrameTooLarge { length: 5, max_frame_length: 4 }));
+        assert!(buf.is_empty());
+
+        codec.encode(Bytes::from_static(b"hell"), &mut buf).unwrap();
+        assert_eq!(buf.len(), 8);
+    }
+
+    #[test]
+    fn builder_defaults_match_new() {
+        assert_eq!(FrameCodec::builder().build(), FrameCodec::new());
+    }
+
+    #[test]
+    fn little_endian_u16_prefix() {
+        let mut codec = FrameCodec::builder()
+            .length_prefix(LengthPrefix::U16)
+            .little_endian()
+            .build();
+        let mut buf = BytesMut::new();
+        codec.encode(Bytes::from_static(b"abc"), &mut buf).unwrap();
+        assert_eq!(&buf[..], &[3, 0, b'a', b'b', b'c']);
+
+        let frame = codec.decode(&mut buf).unwrap();
+        assert_eq!(frame, Some(Bytes::from_static(b"abc")));
+    }
+
+    #[test]
+    fn u64_prefix_including_itself() {
+        let mut codec = FrameCodec::builder()
+            .length_prefix(LengthPrefix::U64)
+            .length_includes_prefix(true)
+            .build();
+        let mut buf = BytesMut::new();
+        codec.encode(Bytes::from_static(b"abc"), &mut buf).unwrap();
+        assert_eq!(&buf[..], &[0, 0, 0, 0, 0, 0, 0, 11, b'a', b'b', b'c']);
+
+        let frame = codec.decode(&mut buf).unwrap();
+        assert_eq!(frame, Some(Bytes::from_static(b"abc")));
+
+        let mut buf = BytesMut::from(&[0, 0, 0, 0, 0, 0, 0, 7][..]);
+        assert_eq!(codec.decode(&mut buf).unwrap_err().kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn u8_prefix_rejects_long_frames() {
+        let mut codec = FrameCodec::builder().length_prefix(LengthPrefix::U8).build();
+        let mut buf = BytesMut::new();
+        let err = codec.encode(Bytes::from(vec![0; 256]), &mut buf).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn varint_prefix_roundtrip() {
+        let mut codec = FrameCodec::builder().length_prefix(LengthPrefix::Varint).build();
+        let mut buf = BytesMut::new();
+        codec.encode(Bytes::from_static(b"abc"), &mut buf).unwrap();
+        assert_eq!(&buf[..], &[3, b'a', b'b', b'c']);
+
+        let payload = Bytes::from(vec![7; 300]);
+        codec.encode(payload.clone(), &mut buf).unwrap();
+        assert_eq!(&buf[4..6], &[0xAC, 0x02]);
+
+        assert_eq!(codec.decode(&mut buf).unwrap(), Some(Bytes::from_static(b"abc")));
+        assert_eq!(codec.decode(&mut buf).unwrap(), Some(payload));
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn varint_prefix_split_across_reads() {
+        let mut codec = FrameCodec::builder().length_prefix(LengthPrefix::Varint).build();
+        let mut buf = BytesMut::from(&[0xAC][..]);
+        assert_eq!(codec.decode(&mut buf).unwrap(), None);
+        assert_eq!(buf.len(), 1);
+
+        buf.put_u8(0x02);
+        buf.put_slice(&[1; 100]);
+        assert_eq!(codec.decode(&mut buf).unwrap(), None);
+
+        buf.put_slice(&[1; 200]);
+        assert_eq!(codec.decode(&mut buf).unwrap(), Some(Bytes::from(vec![1; 300])));
+    }
+
+    #[test]
+    fn varint_prefix_including_itself() {
+        let mut codec = FrameCodec::builder()
+            .length_prefix(LengthPrefix::Varint)
+            .length_includes_prefix(true)
+            .build();
+        let mut buf = BytesMut::new();
+        let payload = Bytes::from(vec![0; 127]);
+        codec.encode(payload.clone(), &mut buf).unwrap();
+        assert_eq!(&buf[..2], &[0x81, 0x01]);
+
+        assert_eq!(codec.decode(&mut buf).unwrap(), Some(payload));
+    }
+
+    #[test]
+    fn varint_prefix_rejects_malformed_lengths() {
+        let mut codec = FrameCodec::builder().length_prefix(LengthPrefix::Varint).build();
+
+        let mut overlong = BytesMut::from(&[0x83, 0x00][..]);
+        assert_eq!(codec.decode(&mut overlong).unwrap_err().kind(), ErrorKind::InvalidData);
 
-        // if we have read all the data, return the frame
-        if byte_count == self.data.len() as u32 {
-            let frame = self.data.clone().freeze();
-            self.clear();
-            return Ok(Some(frame))
+        let mut overflow = BytesMut::from(&[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x02][..]);
+        assert_eq!(codec.decode(&mut overflow).unwrap_err().kind(), ErrorKind::InvalidData);
+
+        let mut too_long = BytesMut::from(&[0x80; 11][..]);
+        assert_eq!(codec.decode(&mut too_long).unwrap_err().kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn varint_prefix_rejects_oversized_frame() {
+        let mut codec = FrameCodec::builder()
+            .length_prefix(LengthPrefix::Varint)
+            .max_frame_length(100)
+            .build();
+        let mut buf = BytesMut::from(&[0xFF, 0xFF, 0xFF, 0xFF, 0x0F][..]);
+
+        let err = codec.decode(&mut buf).unwrap_err();
+        assert_eq!(FrameTooLarge::from_io(&err), Some(&FrameTooLarge { length: u32::MAX as usize, max_frame_length: 100 }));
+    }
+
+    #[test]
+    fn typed_codec_roundtrip() {
+        let mut codec = TypedFrameCodec::<(u32, String)>::new();
+        let mut buf = BytesMut::new();
+        codec.encode((7, "seven".to_string()), &mut buf).unwrap();
+        assert_eq!(&buf[..4], &[0, 0, 0, 13]);
+
+        let value = codec.decode(&mut buf).unwrap();
+        assert_eq!(value, Some((7, "seven".to_string())));
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn typed_codec_distinguishes_decode_errors() {
+        let mut codec = TypedFrameCodec::<u32, u32>::with_codec(FrameCodec::new().with_max_frame_length(8));
+
+        let mut short = BytesMut::from(&[0, 0, 0, 2, 0, 1][..]);
+        assert!(matches!(codec.decode(&mut short), Err(TypedCodecError::Decode(_))));
+
+        let mut oversized = BytesMut::from(&[0, 0, 0, 9][..]);
+        assert!(matches!(codec.decode(&mut oversized), Err(TypedCodecError::Io(_))));
+    }
+
+    #[test]
+    fn typed_codec_rejects_unencodable_values() {
+        struct Name(String);
+
+        impl IntoFrame for Name {
+            fn extend_frame(&self, frame: &mut BytesMut) {
+                crate::utils::expect_encoded(self.try_extend_frame(frame));
+            }
+
+            fn try_extend_frame(&self, frame: &mut BytesMut) -> Result<(), crate::EncodeError> {
+                crate::utils::try_put_length(frame, self.0.len(), LengthPrefix::U8)?;
+                frame.put_slice(self.0.as_bytes());
+                Ok(())
+            }
         }
 
-        // otherwise, wait for more data to arrive to finish the frame
-        Ok(None)
+        let mut codec = TypedFrameCodec::<(), Name>::new();
+        let mut buf = BytesMut::new();
+        let err = codec.encode(Name("a".repeat(256)), &mut buf).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+        assert_eq!(err.to_string(), "length 256 does not fit into a U8 length prefix");
+        assert!(buf.is_empty());
+
+        codec.encode(Name("a".repeat(255)), &mut buf).unwrap();
+        assert_eq!(buf.len(), 4 + 256);
+    }
+
+    #[test]
+    fn decode_partial_frame_stays_in_source() {
+        let mut codec = FrameCodec::new();
+        let mut buf = BytesMut::from(&[0, 0, 0, 6, b'a', b'b', b'c'][..]);
+        assert_eq!(codec.decode(&mut buf).unwrap(), None);
+        assert_eq!(&buf[..], b"abc");
+
+        buf.put_slice(b"def");
+        buf.put_slice(&[0, 0, 0, 0]);
+        assert_eq!(codec.decode(&mut buf).unwrap(), Some(Bytes::from_static(b"abcdef")));
+        assert_eq!(codec.decode(&mut buf).unwrap(), Some(Bytes::new()));
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn typed_codec_applies_limits() {
+        let limits = DecodeLimits::new().with_max_collection_length(2);
+        let mut codec = TypedFrameCodec::<Vec<u8>>::new().with_limits(limits);
+        let mut buf = BytesMut::new();
+        codec.encode(vec![1, 2, 3], &mut buf).unwrap();
+
+        assert!(matches!(codec.decode(&mut buf), Err(TypedCodecError::Decode(_))));
+    }
+
+    #[test]
+    fn typed_codec_strict() {
+        let mut buf = BytesMut::from(&[0, 0, 0, 3, 0, 1, 2][..]);
+        let mut lenient = TypedFrameCodec::<u16>::new();
+        assert_eq!(lenient.decode(&mut buf.clone()).unwrap(), Some(1));
+
+        let mut strict = TypedFrameCodec::<u16>::new().with_strict(true);
+        match strict.decode(&mut buf) {
+            Err(TypedCodecError::Decode(err)) => assert_eq!(err.kind(), &crate::DecodeErrorKind::TrailingBytes { count: 1 }),
+            other => panic!("unexpected result: {:?}", other),
+        }
     }
 }
@@ -1,11 +1,19 @@
-use std::hash::Hash;
-use std::collections::HashSet;
-use bytes::BufMut;
+use std::borrow::Cow;
+use std::cmp::Reverse;
+use std::hash::{BuildHasher, Hash};
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap, HashSet, LinkedList, VecDeque};
+use std::marker::PhantomData;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+use std::num::{NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroIsize, NonZeroU128, NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU8, NonZeroUsize, Wrapping};
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use bytes::{Buf, BufMut};
 
 use super::*;
 
 impl FromFrame for bool {
-	type Error = std::io::Error;
+	type Error = DecodeError;
 
 	fn parse_frame(frame: &mut Bytes) -> Result<Self, Self::Error> {
 		utils::get_bool(frame, "bool")
@@ -13,15 +21,23 @@ impl FromFrame for bool {
 }
 
 impl FromFrame for u8 {
-	type Error = std::io::Error;
+	type Error = DecodeError;
 
 	fn parse_frame(frame: &mut Bytes) -> Result<Self, Self::Error> {
 		utils::get_u8(frame, "u8")
 	}
+
+	fn parse_array<const N: usize>(frame: &mut Bytes) -> Result<[Self; N], DecodeError> {
+		utils::get_byte_array(frame, "array")
+	}
+
+	fn parse_vec(frame: &mut Bytes, prefix: LengthPrefix) -> Result<Vec<Self>, DecodeError> {
+		utils::get_byte_vec_with_prefix(frame, "array", prefix)
+	}
 }
 
 impl FromFrame for u16 {
-	type Error = std::io::Error;
+	type Error = DecodeError;
 
 	fn parse_frame(frame: &mut Bytes) -> Result<Self, Self::Error> {
 		utils::get_u16(frame, "u16")
@@ -29,7 +45,7 @@ impl FromFrame for u16 {
 }
 
 impl FromFrame for u32 {
-	type Error = std::io::Error;
+	type Error = DecodeError;
 
 	fn parse_frame(frame: &mut Bytes) -> Result<Self, Self::Error> {
 		utils::get_u32(frame, "u32")
@@ -37,7 +53,7 @@ impl FromFrame for u32 {
 }
 
 impl FromFrame for u64 {
-	type Error = std::io::Error;
+	type Error = DecodeError;
 
 	fn parse_frame(frame: &mut Bytes) -> Result<Self, Self::Error> {
 		utils::get_u64(frame, "u64")
@@ -45,7 +61,7 @@ impl FromFrame for u64 {
 }
 
 impl FromFrame for i8 {
-	type Error = std::io::Error;
+	type Error = DecodeError;
 
 	fn parse_frame(frame: &mut Bytes) -> Result<Self, Self::Error> {
 		utils::get_i8(frame, "i8")
@@ -53,7 +69,7 @@ impl FromFrame for i8 {
 }
 
 impl FromFrame for i16 {
-	type Error = std::io::Error;
+	type Error = DecodeError;
 
 	fn parse_frame(frame: &mut Bytes) -> Result<Self, Self::Error> {
 		utils::get_i16(frame, "i16")
@@ -61,7 +77,7 @@ impl FromFrame for i16 {
 }
 
 impl FromFrame for i32 {
-	type Error = std::io::Error;
+	type Error = DecodeError;
 
 	fn parse_frame(frame: &mut Bytes) -> Result<Self, Self::Error> {
 		utils::get_i32(frame, "i32")
@@ -69,16 +85,71 @@ impl FromFrame for i32 {
 }
 
 impl FromFrame for i64 {
-	type Error = std::io::Error;
+	type Error = DecodeError;
 
 	fn parse_frame(frame: &mut Bytes) -> Result<Self, Self::Error> {
 		utils::get_i64(frame, "i64")
 	}
 }
 
+impl FromFrame for u128 {
+	type Error = DecodeError;
+
+	fn parse_frame(frame: &mut Bytes) -> Result<Self, Self::Error> {
+		utils::get_u128(frame, "u128")
+	}
+}
+
+impl FromFrame for i128 {
+	type Error = DecodeError;
+
+	fn parse_frame(frame: &mut Bytes) -> Result<Self, Self::Error> {
+		utils::get_i128(frame, "i128")
+	}
+}
+
+impl FromFrame for usize {
+	type Error = DecodeError;
+
+	fn parse_frame(frame: &mut Bytes) -> Result<Self, Self::Error> {
+		utils::get_usize(frame, "usize")
+	}
+}
+
+impl FromFrame for isize {
+	type Error = DecodeError;
+
+	fn parse_frame(frame: &mut Bytes) -> Result<Self, Self::Error> {
+		utils::get_isize(frame, "isize")
+	}
+}
+
+impl FromFrame for f32 {
+	type Error = DecodeError;
+
+	fn parse_frame(frame: &mut Bytes) -> Result<Self, Self::Error> {
+		utils::get_f32(frame, "f32")
+	}
+}
+
+impl FromFrame for f64 {
+	type Error = DecodeError;
+
+	fn parse_frame(frame: &mut Bytes) -> Result<Self, Self::Error> {
+		utils::get_f64(frame, "f64")
+	}
+}
+
+impl FromFrame for char {
+	type Error = DecodeError;
+
+	fn parse_frame(frame: &mut Bytes) -> Result<Self, Self::Error> {
+		utils::get_char(frame, "char")
+	}
+}
 
 impl FromFrame for String {
-	type Error = std::io::Error;
+	type Error = DecodeError;
 
 	fn parse_frame(frame: &mut Bytes) -> Result<Self, Self::Error> {
 		utils::get_string(frame, "string")
@@ -88,427 +159,1699 @@ impl FromFrame for String {
 impl<T> FromFrame for Option<T>
 where
 	T: FromFrame,
-	<T as FromFrame>::Error: Into<std::io::Error>,
+	<T as FromFrame>::Error: Into<DecodeError>,
+{
+	type Error = DecodeError;
+
+	fn parse_frame(frame: &mut Bytes) -> Result<Self, Self::Error> {
+		utils::get_option(frame, "option", |frame| <T as FromFrame>::parse_frame(frame))
+	}
+}
+
+impl<T, E> FromFrame for Result<T, E>
+where
+	T: FromFrame,
+	E: FromFrame,
+	<T as FromFrame>::Error: Into<DecodeError>,
+	<E as FromFrame>::Error: Into<DecodeError>,
 {
-	type Error = std::io::Error;
+	type Error = DecodeError;
 
 	fn parse_frame(frame: &mut Bytes) -> Result<Self, Self::Error> {
-		utils::get_option(frame, "option", |frame| <T as FromFrame>::parse_frame(frame).map_err(Into::into))
+		utils::get_result(frame, "result", |frame| <T as FromFrame>::parse_frame(frame), |frame| <E as FromFrame>::parse_frame(frame))
 	}
 }
 
 impl<T> FromFrame for Vec<T>
 where
 	T: FromFrame,
-	<T as FromFrame>::Error: Into<std::io::Error>,
+	<T as FromFrame>::Error: Into<DecodeError>,
 {
-	type Error = std::io::Error;
+	type Error = DecodeError;
 
 	fn parse_frame(frame: &mut Bytes) -> Result<Self, Self::Error> {
-		utils::get_array(frame, "array", |frame| <T as FromFrame>::parse_frame(frame).map_err(Into::into))
+		T::parse_vec(frame, LengthPrefix::U32)
 	}
 }
 
-impl<T> FromFrame for HashSet<T>
+impl<T, S> FromFrame for HashSet<T, S>
 where
-	T: FromFrame + PartialEq + Eq + Hash,
-	<T as FromFrame>::Error: Into<std::io::Error>,
+	T: FromFrame + Eq + Hash,
+	S: BuildHasher + Default,
+	<T as FromFrame>::Error: Into<DecodeError>,
 {
-	type Error = std::io::Error;
+	type Error = DecodeError;
 
 	fn parse_frame(frame: &mut Bytes) -> Result<Self, Self::Error> {
-		utils::get_hashset(frame, "hashset", |frame| <T as FromFrame>::parse_frame(frame).map_err(Into::into))
+		utils::get_set(frame, "hashset", |frame| <T as FromFrame>::parse_frame(frame))
 	}
 }
 
-impl IntoFrame for bool {
-	fn extend_frame(&self, frame: &mut BytesMut) {
-		frame.put_u8(*self as u8)
+impl<T> FromFrame for BTreeSet<T>
+where
+	T: FromFrame + Ord,
+	<T as FromFrame>::Error: Into<DecodeError>,
+{
+	type Error = DecodeError;
+
+	fn parse_frame(frame: &mut Bytes) -> Result<Self, Self::Error> {
+		utils::get_set(frame, "btreeset", |frame| <T as FromFrame>::parse_frame(frame))
 	}
 }
 
-impl IntoFrame for u8 {
-	fn extend_frame(&self, frame: &mut BytesMut) {
-		frame.put_u8(*self)
+impl<K, V, S> FromFrame for HashMap<K, V, S>
+where
+	K: FromFrame + Eq + Hash,
+	V: FromFrame,
+	S: BuildHasher + Default,
+	<K as FromFrame>::Error: Into<DecodeError>,
+	<V as FromFrame>::Error: Into<DecodeError>,
+{
+	type Error = DecodeError;
+
+	fn parse_frame(frame: &mut Bytes) -> Result<Self, Self::Error> {
+		utils::get_map(frame, "hashmap", |frame| <K as FromFrame>::parse_frame(frame), |frame| <V as FromFrame>::parse_frame(frame))
 	}
 }
 
-impl IntoFrame for u16 {
-	fn extend_frame(&self, frame: &mut BytesMut) {
-		frame.put_u16(*self)
+impl<K, V> FromFrame for BTreeMap<K, V>
+where
+	K: FromFrame + Ord,
+	V: FromFrame,
+	<K as FromFrame>::Error: Into<DecodeError>,
+	<V as FromFrame>::Error: Into<DecodeError>,
+{
+	type Error = DecodeError;
+
+	fn parse_frame(frame: &mut Bytes) -> Result<Self, Self::Error> {
+		utils::get_map(frame, "btreemap", |frame| <K as FromFrame>::parse_frame(frame), |frame| <V as FromFrame>::parse_frame(frame))
 	}
 }
 
-impl IntoFrame for u32 {
-	fn extend_frame(&self, frame: &mut BytesMut) {
-		frame.put_u32(*self)
+impl<T> FromFrame for VecDeque<T>
+where
+	T: FromFrame,
+	<T as FromFrame>::Error: Into<DecodeError>,
+{
+	type Error = DecodeError;
+
+	fn parse_frame(frame: &mut Bytes) -> Result<Self, Self::Error> {
+		utils::get_array(frame, "vecdeque", |frame| <T as FromFrame>::parse_frame(frame)).map(VecDeque::from)
 	}
 }
 
-impl IntoFrame for u64 {
-	fn extend_frame(&self, frame: &mut BytesMut) {
-		frame.put_u64(*self)
+impl<T> FromFrame for LinkedList<T>
+where
+	T: FromFrame,
+	<T as FromFrame>::Error: Into<DecodeError>,
+{
+	type Error = DecodeError;
+
+	fn parse_frame(frame: &mut Bytes) -> Result<Self, Self::Error> {
+		utils::get_array(frame, "linkedlist", |frame| <T as FromFrame>::parse_frame(frame)).map(LinkedList::from_iter)
 	}
 }
 
-impl IntoFrame for i8 {
-	fn extend_frame(&self, frame: &mut BytesMut) {
-		frame.put_i8(*self)
+impl<T> FromFrame for BinaryHeap<T>
+where
+	T: FromFrame + Ord,
+	<T as FromFrame>::Error: Into<DecodeError>,
+{
+	type Error = DecodeError;
+
+	fn parse_frame(frame: &mut Bytes) -> Result<Self, Self::Error> {
+		utils::get_array(frame, "binaryheap", |frame| <T as FromFrame>::parse_frame(frame)).map(BinaryHeap::from)
 	}
 }
 
-impl IntoFrame for i16 {
-	fn extend_frame(&self, frame: &mut BytesMut) {
-		frame.put_i16(*self)
+impl<T, const N: usize> FromFrame for [T; N]
+where
+	T: FromFrame,
+	<T as FromFrame>::Error: Into<DecodeError>,
+{
+	type Error = DecodeError;
+
+	fn parse_frame(frame: &mut Bytes) -> Result<Self, Self::Error> {
+		T::parse_array(frame)
 	}
 }
 
-impl IntoFrame for i32 {
-	fn extend_frame(&self, frame: &mut BytesMut) {
-		frame.put_i32(*self)
+impl<T: FromFrame> FromFrame for Box<T> {
+	type Error = <T as FromFrame>::Error;
+
+	fn parse_frame(frame: &mut Bytes) -> Result<Self, Self::Error> {
+		<T as FromFrame>::parse_frame(frame).map(Box::new)
 	}
 }
 
-impl IntoFrame for i64 {
-	fn extend_frame(&self, frame: &mut BytesMut) {
-		frame.put_i64(*self)
+impl<T: FromFrame> FromFrame for Rc<T> {
+	type Error = <T as FromFrame>::Error;
+
+	fn parse_frame(frame: &mut Bytes) -> Result<Self, Self::Error> {
+		<T as FromFrame>::parse_frame(frame).map(Rc::new)
 	}
 }
 
-impl IntoFrame for &str {
-	fn extend_frame(&self, frame: &mut BytesMut) {
-		frame.put_u32(self.len() as u32);
-		frame.put_slice(self.as_bytes());
+impl<T: FromFrame> FromFrame for Arc<T> {
+	type Error = <T as FromFrame>::Error;
+
+	fn parse_frame(frame: &mut Bytes) -> Result<Self, Self::Error> {
+		<T as FromFrame>::parse_frame(frame).map(Arc::new)
 	}
+}
 
-	fn size_hint(&self) -> usize {
-		4 + self.len()
+impl<T: FromFrame> FromFrame for Wrapping<T> {
+	type Error = <T as FromFrame>::Error;
+
+	fn parse_frame(frame: &mut Bytes) -> Result<Self, Self::Error> {
+		<T as FromFrame>::parse_frame(frame).map(Wrapping)
 	}
 }
 
-impl IntoFrame for String {
-	fn extend_frame(&self, frame: &mut BytesMut) {
-		<&str as IntoFrame>::extend_frame(&self.as_str(), frame)
+impl<T: FromFrame> FromFrame for Reverse<T> {
+	type Error = <T as FromFrame>::Error;
+
+	fn parse_frame(frame: &mut Bytes) -> Result<Self, Self::Error> {
+		<T as FromFrame>::parse_frame(frame).map(Reverse)
 	}
+}
 
-	fn size_hint(&self) -> usize {
-		<&str as IntoFrame>::size_hint(&self.as_str())
+impl<T> FromFrame for Cow<'_, T>
+where
+	T: ToOwned + ?Sized,
+	<T as ToOwned>::Owned: FromFrame,
+{
+	type Error = <<T as ToOwned>::Owned as FromFrame>::Error;
+
+	fn parse_frame(frame: &mut Bytes) -> Result<Self, Self::Error> {
+		<<T as ToOwned>::Owned as FromFrame>::parse_frame(frame).map(Cow::Owned)
 	}
 }
 
-impl<T: IntoFrame> IntoFrame for Option<T> {
-	fn extend_frame(&self, frame: &mut BytesMut) {
-		utils::put_option(frame, self, |frame, value| <T as IntoFrame>::extend_frame(value, frame));
+impl<T: ?Sized> FromFrame for PhantomData<T> {
+	type Error = DecodeError;
+
+	fn parse_frame(_frame: &mut Bytes) -> Result<Self, Self::Error> {
+		Ok(PhantomData)
 	}
+}
 
-	fn size_hint(&self) -> usize {
-		1 + self.as_ref().map(|value| value.size_hint()).unwrap_or(0)
+macro_rules! std_type_impls {
+	($($ty:ident => $get:ident, $put:ident, |$this:pat_param| $len:expr);* $(;)?) => {
+		$(
+			impl FromFrame for $ty {
+				type Error = DecodeError;
+
+				fn parse_frame(frame: &mut Bytes) -> Result<Self, Self::Error> {
+					utils::$get(frame, stringify!($ty))
+				}
+			}
+
+			impl<'a> FromFrameRef<'a> for $ty {
+				type Error = DecodeError;
+
+				fn parse_frame_ref(frame: &mut &'a [u8]) -> Result<Self, Self::Error> {
+					utils::$get(frame, stringify!($ty))
+				}
+			}
+
+			impl IntoFrame for $ty {
+				fn extend_frame(&self, frame: &mut BytesMut) {
+					utils::$put(frame, self);
+				}
+
+				fn size_hint(&self) -> usize {
+					self.encoded_len()
+				}
+
+				fn encoded_len(&self) -> usize {
+					let $this = self;
+					$len
+				}
+			}
+		)*
+	};
+}
+
+std_type_impls! {
+	Duration => get_duration, put_duration, |_| 12;
+	Ipv4Addr => get_ipv4_addr, put_ipv4_addr, |_| 4;
+	Ipv6Addr => get_ipv6_addr, put_ipv6_addr, |_| 16;
+	IpAddr => get_ip_addr, put_ip_addr, |ip| if ip.is_ipv4() { 5 } else { 17 };
+	SocketAddrV4 => get_socket_addr_v4, put_socket_addr_v4, |_| 6;
+	SocketAddrV6 => get_socket_addr_v6, put_socket_addr_v6, |_| 26;
+	SocketAddr => get_socket_addr, put_socket_addr, |addr| if addr.is_ipv4() { 7 } else { 27 };
+}
+
+impl FromFrame for SystemTime {
+	type Error = DecodeError;
+
+	fn parse_frame(frame: &mut Bytes) -> Result<Self, Self::Error> {
+		utils::get_system_time(frame, "SystemTime")
 	}
 }
 
-impl<T: IntoFrame> IntoFrame for Vec<T> {
+impl<'a> FromFrameRef<'a> for SystemTime {
+	type Error = DecodeError;
+
+	fn parse_frame_ref(frame: &mut &'a [u8]) -> Result<Self, Self::Error> {
+		utils::get_system_time(frame, "SystemTime")
+	}
+}
+
+impl IntoFrame for SystemTime {
 	fn extend_frame(&self, frame: &mut BytesMut) {
-		utils::put_array(frame, self, |frame, value| <T as IntoFrame>::extend_frame(value, frame));
+		utils::put_system_time(frame, self);
+	}
+
+	fn try_extend_frame(&self, frame: &mut BytesMut) -> Result<(), EncodeError> {
+		utils::try_put_system_time(frame, self)
 	}
 
 	fn size_hint(&self) -> usize {
-		4 + self.iter().map(|value| value.size_hint()).sum::<usize>()
+		self.encoded_len()
+	}
+
+	fn encoded_len(&self) -> usize {
+		12
 	}
 }
 
-impl<T: IntoFrame + PartialEq + Eq + Hash> IntoFrame for HashSet<T> {
-	fn extend_frame(&self, frame: &mut BytesMut) {
-		utils::put_hashset(frame, self, |frame, value| <T as IntoFrame>::extend_frame(value, frame));
+macro_rules! non_zero_impls {
+	($($ty:ident => $get:ident),* $(,)?) => {
+		$(
+			impl FromFrame for $ty {
+				type Error = DecodeError;
+
+				fn parse_frame(frame: &mut Bytes) -> Result<Self, Self::Error> {
+					utils::get_non_zero(frame, stringify!($ty), utils::$get)
+				}
+			}
+
+			impl<'a> FromFrameRef<'a> for $ty {
+				type Error = DecodeError;
+
+				fn parse_frame_ref(frame: &mut &'a [u8]) -> Result<Self, Self::Error> {
+					utils::get_non_zero(frame, stringify!($ty), utils::$get)
+				}
+			}
+
+			impl IntoFrame for $ty {
+				fn extend_frame(&self, frame: &mut BytesMut) {
+					self.get().extend_frame(frame);
+				}
+
+				fn size_hint(&self) -> usize {
+					self.encoded_len()
+				}
+
+				fn encoded_len(&self) -> usize {
+					self.get().encoded_len()
+				}
+			}
+		)*
+	};
+}
+
+non_zero_impls! {
+	NonZeroU8 => get_u8,
+	NonZeroU16 => get_u16,
+	NonZeroU32 => get_u32,
+	NonZeroU64 => get_u64,
+	NonZeroU128 => get_u128,
+	NonZeroUsize => get_usize,
+	NonZeroI8 => get_i8,
+	NonZeroI16 => get_i16,
+	NonZeroI32 => get_i32,
+	NonZeroI64 => get_i64,
+	NonZeroI128 => get_i128,
+	NonZeroIsize => get_isize,
+}
+
+impl<T: FromFrame> FromFrame for Canonical<T> {
+	type Error = <T as FromFrame>::Error;
+
+	fn parse_frame(frame: &mut Bytes) -> Result<Self, Self::Error> {
+		<T as FromFrame>::parse_frame(frame).map(Canonical)
 	}
+}
 
-	fn size_hint(&self) -> usize {
-		4 + self.iter().map(|value| value.size_hint()).sum::<usize>()
+impl FromFrame for Bytes {
+	type Error = DecodeError;
+
+	fn parse_frame(frame: &mut Bytes) -> Result<Self, Self::Error> {
+		utils::get_bytes(frame, "bytes")
 	}
 }
 
-impl<T1, T2> IntoFrame for (T1, T2)
-where
-	T1: IntoFrame,
-	T2: IntoFrame,
-{
-	fn extend_frame(&self, frame: &mut BytesMut) {
-		self.0.extend_frame(frame);
-		self.1.extend_frame(frame);
+impl FromFrame for BytesStr {
+	type Error = DecodeError;
+
+	fn parse_frame(frame: &mut Bytes) -> Result<Self, Self::Error> {
+		utils::get_bytes_str(frame, "string")
 	}
+}
 
-	fn size_hint(&self) -> usize {
-		self.0.size_hint() + self.1.size_hint()
+impl<'a> FromFrameRef<'a> for bool {
+	type Error = DecodeError;
+
+	fn parse_frame_ref(frame: &mut &'a [u8]) -> Result<Self, Self::Error> {
+		utils::get_bool(frame, "bool")
 	}
 }
 
-impl<T1, T2, T3> IntoFrame for (T1, T2, T3)
-where
-	T1: IntoFrame,
-	T2: IntoFrame,
-	T3: IntoFrame,
-{
-	fn extend_frame(&self, frame: &mut BytesMut) {
-		self.0.extend_frame(frame);
-		self.1.extend_frame(frame);
-		self.2.extend_frame(frame);
+impl<'a> FromFrameRef<'a> for u8 {
+	type Error = DecodeError;
+
+	fn parse_frame_ref(frame: &mut &'a [u8]) -> Result<Self, Self::Error> {
+		utils::get_u8(frame, "u8")
 	}
 
-	fn size_hint(&self) -> usize {
-		self.0.size_hint() + self.1.size_hint() + self.2.size_hint()
+	fn parse_array_ref<const N: usize>(frame: &mut &'a [u8]) -> Result<[Self; N], DecodeError> {
+		utils::get_byte_array(frame, "array")
+	}
+
+	fn parse_vec_ref(frame: &mut &'a [u8], prefix: LengthPrefix) -> Result<Vec<Self>, DecodeError> {
+		utils::get_byte_vec_with_prefix(frame, "array", prefix)
 	}
 }
 
-impl<T1, T2, T3, T4> IntoFrame for (T1, T2, T3, T4)
-where
-	T1: IntoFrame,
-	T2: IntoFrame,
-	T3: IntoFrame,
-	T4: IntoFrame,
-{
-	fn extend_frame(&self, frame: &mut BytesMut) {
-		self.0.extend_frame(frame);
-		self.1.extend_frame(frame);
-		self.2.extend_frame(frame);
-		self.3.extend_frame(frame);
+impl<'a> FromFrameRef<'a> for u16 {
+	type Error = DecodeError;
+
+	fn parse_frame_ref(frame: &mut &'a [u8]) -> Result<Self, Self::Error> {
+		utils::get_u16(frame, "u16")
 	}
+}
 
-	fn size_hint(&self) -> usize {
-		self.0.size_hint() + self.1.size_hint() + self.2.size_hint() + self.3.size_hint()
+impl<'a> FromFrameRef<'a> for u32 {
+	type Error = DecodeError;
+
+	fn parse_frame_ref(frame: &mut &'a [u8]) -> Result<Self, Self::Error> {
+		utils::get_u32(frame, "u32")
 	}
 }
 
-impl<T1, T2, T3, T4, T5> IntoFrame for (T1, T2, T3, T4, T5)
-where
-	T1: IntoFrame,
-	T2: IntoFrame,
-	T3: IntoFrame,
-	T4: IntoFrame,
-	T5: IntoFrame,
-{
-	fn extend_frame(&self, frame: &mut BytesMut) {
-		self.0.extend_frame(frame);
-		self.1.extend_frame(frame);
-		self.2.extend_frame(frame);
-		self.3.extend_frame(frame);
-		self.4.extend_frame(frame);
+impl<'a> FromFrameRef<'a> for u64 {
+	type Error = DecodeError;
+
+	fn parse_frame_ref(frame: &mut &'a [u8]) -> Result<Self, Self::Error> {
+		utils::get_u64(frame, "u64")
 	}
+}
 
-	fn size_hint(&self) -> usize {
-		self.0.size_hint() + self.1.size_hint() + self.2.size_hint() + self.3.size_hint() + self.4.size_hint()
+impl<'a> FromFrameRef<'a> for i8 {
+	type Error = DecodeError;
+
+	fn parse_frame_ref(frame: &mut &'a [u8]) -> Result<Self, Self::Error> {
+		utils::get_i8(frame, "i8")
 	}
 }
 
-impl<T1, T2, T3, T4, T5, T6> IntoFrame for (T1, T2, T3, T4, T5, T6)
-where
-	T1: IntoFrame,
-	T2: IntoFrame,
-	T3: IntoFrame,
-	T4: IntoFrame,
-	T5: IntoFrame,
-	T6: IntoFrame,
-{
-	fn extend_frame(&self, frame: &mut BytesMut) {
-		self.0.extend_frame(frame);
-		self.1.extend_frame(frame);
-		self.2.extend_frame(frame);
-		self.3.extend_frame(frame);
-		self.4.extend_frame(frame);
-		self.5.extend_frame(frame);
+impl<'a> FromFrameRef<'a> for i16 {
+	type Error = DecodeError;
+
+	fn parse_frame_ref(frame: &mut &'a [u8]) -> Result<Self, Self::Error> {
+		utils::get_i16(frame, "i16")
 	}
+}
 
-	fn size_hint(&self) -> usize {
-		self.0.size_hint() + self.1.size_hint() + self.2.size_hint() + self.3.size_hint() + self.4.size_hint() + self.5.size_hint()
+impl<'a> FromFrameRef<'a> for i32 {
+	type Error = DecodeError;
+
+	fn parse_frame_ref(frame: &mut &'a [u8]) -> Result<Self, Self::Error> {
+		utils::get_i32(frame, "i32")
 	}
 }
 
-impl<T1, T2, T3, T4, T5, T6, T7> IntoFrame for (T1, T2, T3, T4, T5, T6, T7)
-where
-	T1: IntoFrame,
-	T2: IntoFrame,
-	T3: IntoFrame,
-	T4: IntoFrame,
-	T5: IntoFrame,
-	T6: IntoFrame,
-	T7: IntoFrame,
-{
-	fn extend_frame(&self, frame: &mut BytesMut) {
-		self.0.extend_frame(frame);
-		self.1.extend_frame(frame);
-		self.2.extend_frame(frame);
-		self.3.extend_frame(frame);
-		self.4.extend_frame(frame);
-		self.5.extend_frame(frame);
-		self.6.extend_frame(frame);
+impl<'a> FromFrameRef<'a> for i64 {
+	type Error = DecodeError;
+
+	fn parse_frame_ref(frame: &mut &'a [u8]) -> Result<Self, Self::Error> {
+		utils::get_i64(frame, "i64")
+	}
+}
+
+impl<'a> FromFrameRef<'a> for u128 {
+	type Error = DecodeError;
+
+	fn parse_frame_ref(frame: &mut &'a [u8]) -> Result<Self, Self::Error> {
+		utils::get_u128(frame, "u128")
 	}
+}
 
-	fn size_hint(&self) -> usize {
-		self.0.size_hint() + self.1.size_hint() + self.2.size_hint() + self.3.size_hint() + self.4.size_hint() + self.5.size_hint() + self.6.size_hint()
+impl<'a> FromFrameRef<'a> for i128 {
+	type Error = DecodeError;
+
+	fn parse_frame_ref(frame: &mut &'a [u8]) -> Result<Self, Self::Error> {
+		utils::get_i128(frame, "i128")
+	}
+}
+
+impl<'a> FromFrameRef<'a> for usize {
+	type Error = DecodeError;
+
+	fn parse_frame_ref(frame: &mut &'a [u8]) -> Result<Self, Self::Error> {
+		utils::get_usize(frame, "usize")
+	}
+}
+
+impl<'a> FromFrameRef<'a> for isize {
+	type Error = DecodeError;
+
+	fn parse_frame_ref(frame: &mut &'a [u8]) -> Result<Self, Self::Error> {
+		utils::get_isize(frame, "isize")
+	}
+}
+
+impl<'a> FromFrameRef<'a> for f32 {
+	type Error = DecodeError;
+
+	fn parse_frame_ref(frame: &mut &'a [u8]) -> Result<Self, Self::Error> {
+		utils::get_f32(frame, "f32")
+	}
+}
+
+impl<'a> FromFrameRef<'a> for f64 {
+	type Error = DecodeError;
+
+	fn parse_frame_ref(frame: &mut &'a [u8]) -> Result<Self, Self::Error> {
+		utils::get_f64(frame, "f64")
+	}
+}
+
+impl<'a> FromFrameRef<'a> for char {
+	type Error = DecodeError;
+
+	fn parse_frame_ref(frame: &mut &'a [u8]) -> Result<Self, Self::Error> {
+		utils::get_char(frame, "char")
+	}
+}
+
+impl<'a> FromFrameRef<'a> for &'a str {
+	type Error = DecodeError;
+
+	fn parse_frame_ref(frame: &mut &'a [u8]) -> Result<Self, Self::Error> {
+		utils::get_str(frame, "string")
+	}
+}
+
+impl<'a> FromFrameRef<'a> for &'a [u8] {
+	type Error = DecodeError;
+
+	fn parse_frame_ref(frame: &mut &'a [u8]) -> Result<Self, Self::Error> {
+		utils::get_slice(frame, "bytes")
+	}
+}
+
+impl<'a> FromFrameRef<'a> for String {
+	type Error = DecodeError;
+
+	fn parse_frame_ref(frame: &mut &'a [u8]) -> Result<Self, Self::Error> {
+		utils::get_string(frame, "string")
 	}
 }
 
-impl<T1, T2, T3, T4, T5, T6, T7, T8> IntoFrame for (T1, T2, T3, T4, T5, T6, T7, T8)
+impl<'a, T> FromFrameRef<'a> for Option<T>
 where
-	T1: IntoFrame,
-	T2: IntoFrame,
-	T3: IntoFrame,
-	T4: IntoFrame,
-	T5: IntoFrame,
-	T6: IntoFrame,
-	T7: IntoFrame,
-	T8: IntoFrame,
+	T: FromFrameRef<'a>,
+	<T as FromFrameRef<'a>>::Error: Into<DecodeError>,
 {
-	fn extend_frame(&self, frame: &mut BytesMut) {
-		self.0.extend_frame(frame);
-		self.1.extend_frame(frame);
-		self.2.extend_frame(frame);
-		self.3.extend_frame(frame);
-		self.4.extend_frame(frame);
-		self.5.extend_frame(frame);
-		self.6.extend_frame(frame);
-		self.7.extend_frame(frame);
-	}
+	type Error = DecodeError;
 
-	fn size_hint(&self) -> usize {
-		self.0.size_hint() + self.1.size_hint() + self.2.size_hint() + self.3.size_hint() + self.4.size_hint() + self.5.size_hint() + self.6.size_hint() + self.7.size_hint()
+	fn parse_frame_ref(frame: &mut &'a [u8]) -> Result<Self, Self::Error> {
+		utils::get_option(frame, "option", |frame| <T as FromFrameRef<'a>>::parse_frame_ref(frame))
 	}
 }
 
-impl<T1, T2> FromFrame for (T1, T2)
+impl<'a, T, E> FromFrameRef<'a> for Result<T, E>
 where
-	T1: FromFrame,
-	T2: FromFrame,
-	<T1 as FromFrame>::Error: Into<std::io::Error>,
-	<T2 as FromFrame>::Error: Into<std::io::Error>,
+	T: FromFrameRef<'a>,
+	E: FromFrameRef<'a>,
+	<T as FromFrameRef<'a>>::Error: Into<DecodeError>,
+	<E as FromFrameRef<'a>>::Error: Into<DecodeError>,
 {
-	type Error = std::io::Error;
+	type Error = DecodeError;
 
-	fn parse_frame(frame: &mut Bytes) -> Result<Self, Self::Error> {
-		Ok((T1::parse_frame(frame).map_err(Into::into)?, T2::parse_frame(frame).map_err(Into::into)?))
+	fn parse_frame_ref(frame: &mut &'a [u8]) -> Result<Self, Self::Error> {
+		utils::get_result(frame, "result", |frame| <T as FromFrameRef<'a>>::parse_frame_ref(frame), |frame| <E as FromFrameRef<'a>>::parse_frame_ref(frame))
 	}
 }
 
-impl<T1, T2, T3> FromFrame for (T1, T2, T3)
+impl<'a, T> FromFrameRef<'a> for Vec<T>
 where
-	T1: FromFrame,
-	T2: FromFrame,
-	T3: FromFrame,
-	<T1 as FromFrame>::Error: Into<std::io::Error>,
-	<T2 as FromFrame>::Error: Into<std::io::Error>,
-	<T3 as FromFrame>::Error: Into<std::io::Error>,
+	T: FromFrameRef<'a>,
+	<T as FromFrameRef<'a>>::Error: Into<DecodeError>,
 {
-	type Error = std::io::Error;
+	type Error = DecodeError;
 
-	fn parse_frame(frame: &mut Bytes) -> Result<Self, Self::Error> {
-		Ok((T1::parse_frame(frame).map_err(Into::into)?, T2::parse_frame(frame).map_err(Into::into)?, T3::parse_frame(frame).map_err(Into::into)?))
+	fn parse_frame_ref(frame: &mut &'a [u8]) -> Result<Self, Self::Error> {
+		T::parse_vec_ref(frame, LengthPrefix::U32)
 	}
 }
 
-impl<T1, T2, T3, T4> FromFrame for (T1, T2, T3, T4)
+impl<'a, T, S> FromFrameRef<'a> for HashSet<T, S>
 where
-	T1: FromFrame,
-	T2: FromFrame,
-	T3: FromFrame,
-	T4: FromFrame,
-	<T1 as FromFrame>::Error: Into<std::io::Error>,
-	<T2 as FromFrame>::Error: Into<std::io::Error>,
-	<T3 as FromFrame>::Error: Into<std::io::Error>,
-	<T4 as FromFrame>::Error: Into<std::io::Error>,
+	T: FromFrameRef<'a> + Eq + Hash,
+	S: BuildHasher + Default,
+	<T as FromFrameRef<'a>>::Error: Into<DecodeError>,
 {
-	type Error = std::io::Error;
+	type Error = DecodeError;
 
-	fn parse_frame(frame: &mut Bytes) -> Result<Self, Self::Error> {
-		Ok((T1::parse_frame(frame).map_err(Into::into)?, T2::parse_frame(frame).map_err(Into::into)?, T3::parse_frame(frame).map_err(Into::into)?, T4::parse_frame(frame).map_err(Into::into)?))
+	fn parse_frame_ref(frame: &mut &'a [u8]) -> Result<Self, Self::Error> {
+		utils::get_set(frame, "hashset", |frame| <T as FromFrameRef<'a>>::parse_frame_ref(frame))
 	}
 }
 
-impl<T1, T2, T3, T4, T5> FromFrame for (T1, T2, T3, T4, T5)
+impl<'a, T> FromFrameRef<'a> for BTreeSet<T>
 where
-	T1: FromFrame,
-	T2: FromFrame,
-	T3: FromFrame,
-	T4: FromFrame,
-	T5: FromFrame,
-	<T1 as FromFrame>::Error: Into<std::io::Error>,
-	<T2 as FromFrame>::Error: Into<std::io::Error>,
-	<T3 as FromFrame>::Error: Into<std::io::Error>,
-	<T4 as FromFrame>::Error: Into<std::io::Error>,
-	<T5 as FromFrame>::Error: Into<std::io::Error>,
+	T: FromFrameRef<'a> + Ord,
+	<T as FromFrameRef<'a>>::Error: Into<DecodeError>,
 {
-	type Error = std::io::Error;
+	type Error = DecodeError;
 
-	fn parse_frame(frame: &mut Bytes) -> Result<Self, Self::Error> {
-		Ok((T1::parse_frame(frame).map_err(Into::into)?, T2::parse_frame(frame).map_err(Into::into)?, T3::parse_frame(frame).map_err(Into::into)?, T4::parse_frame(frame).map_err(Into::into)?, T5::parse_frame(frame).map_err(Into::into)?))
+	fn parse_frame_ref(frame: &mut &'a [u8]) -> Result<Self, Self::Error> {
+		utils::get_set(frame, "btreeset", |frame| <T as FromFrameRef<'a>>::parse_frame_ref(frame))
 	}
 }
 
-impl<T1, T2, T3, T4, T5, T6> FromFrame for (T1, T2, T3, T4, T5, T6)
+impl<'a, K, V, S> FromFrameRef<'a> for HashMap<K, V, S>
 where
-	T1: FromFrame,
-	T2: FromFrame,
-	T3: FromFrame,
-	T4: FromFrame,
-	T5: FromFrame,
-	T6: FromFrame,
-	<T1 as FromFrame>::Error: Into<std::io::Error>,
-	<T2 as FromFrame>::Error: Into<std::io::Error>,
-	<T3 as FromFrame>::Error: Into<std::io::Error>,
-	<T4 as FromFrame>::Error: Into<std::io::Error>,
-	<T5 as FromFrame>::Error: Into<std::io::Error>,
-	<T6 as FromFrame>::Error: Into<std::io::Error>,
+	K: FromFrameRef<'a> + Eq + Hash,
+	V: FromFrameRef<'a>,
+	S: BuildHasher + Default,
+	<K as FromFrameRef<'a>>::Error: Into<DecodeError>,
+	<V as FromFrameRef<'a>>::Error: Into<DecodeError>,
 {
-	type Error = std::io::Error;
+	type Error = DecodeError;
 
-	fn parse_frame(frame: &mut Bytes) -> Result<Self, Self::Error> {
-		Ok((T1::parse_frame(frame).map_err(Into::into)?, T2::parse_frame(frame).map_err(Into::into)?, T3::parse_frame(frame).map_err(Into::into)?, T4::parse_frame(frame).map_err(Into::into)?, T5::parse_frame(frame).map_err(Into::into)?, T6::parse_frame(frame).map_err(Into::into)?))
+	fn parse_frame_ref(frame: &mut &'a [u8]) -> Result<Self, Self::Error> {
+		utils::get_map(frame, "hashmap", |frame| <K as FromFrameRef<'a>>::parse_frame_ref(frame), |frame| <V as FromFrameRef<'a>>::parse_frame_ref(frame))
 	}
 }
 
-impl<T1, T2, T3, T4, T5, T6, T7> FromFrame for (T1, T2, T3, T4, T5, T6, T7)
+impl<'a, K, V> FromFrameRef<'a> for BTreeMap<K, V>
 where
-	T1: FromFrame,
-	T2: FromFrame,
-	T3: FromFrame,
-	T4: FromFrame,
-	T5: FromFrame,
-	T6: FromFrame,
-	T7: FromFrame,
-	<T1 as FromFrame>::Error: Into<std::io::Error>,
-	<T2 as FromFrame>::Error: Into<std::io::Error>,
-	<T3 as FromFrame>::Error: Into<std::io::Error>,
-	<T4 as FromFrame>::Error: Into<std::io::Error>,
-	<T5 as FromFrame>::Error: Into<std::io::Error>,
-	<T6 as FromFrame>::Error: Into<std::io::Error>,
-	<T7 as FromFrame>::Error: Into<std::io::Error>,
+	K: FromFrameRef<'a> + Ord,
+	V: FromFrameRef<'a>,
+	<K as FromFrameRef<'a>>::Error: Into<DecodeError>,
+	<V as FromFrameRef<'a>>::Error: Into<DecodeError>,
 {
-	type Error = std::io::Error;
+	type Error = DecodeError;
 
-	fn parse_frame(frame: &mut Bytes) -> Result<Self, Self::Error> {
-		Ok((T1::parse_frame(frame).map_err(Into::into)?, T2::parse_frame(frame).map_err(Into::into)?, T3::parse_frame(frame).map_err(Into::into)?, T4::parse_frame(frame).map_err(Into::into)?, T5::parse_frame(frame).map_err(Into::into)?, T6::parse_frame(frame).map_err(Into::into)?, T7::parse_frame(frame).map_err(Into::into)?))
+	fn parse_frame_ref(frame: &mut &'a [u8]) -> Result<Self, Self::Error> {
+		utils::get_map(frame, "btreemap", |frame| <K as FromFrameRef<'a>>::parse_frame_ref(frame), |frame| <V as FromFrameRef<'a>>::parse_frame_ref(frame))
 	}
 }
 
-impl<T1, T2, T3, T4, T5, T6, T7, T8> FromFrame for (T1, T2, T3, T4, T5, T6, T7, T8)
+impl<'a, T> FromFrameRef<'a> for VecDeque<T>
 where
-	T1: FromFrame,
-	T2: FromFrame,
-	T3: FromFrame,
-	T4: FromFrame,
-	T5: FromFrame,
-	T6: FromFrame,
-	T7: FromFrame,
-	T8: FromFrame,
-	<T1 as FromFrame>::Error: Into<std::io::Error>,
-	<T2 as FromFrame>::Error: Into<std::io::Error>,
-	<T3 as FromFrame>::Error: Into<std::io::Error>,
-	<T4 as FromFrame>::Error: Into<std::io::Error>,
-	<T5 as FromFrame>::Error: Into<std::io::Error>,
-	<T6 as FromFrame>::Error: Into<std::io::Error>,
-	<T7 as FromFrame>::Error: Into<std::io::Error>,
-	<T8 as FromFrame>::Error: Into<std::io::Error>,
+	T: FromFrameRef<'a>,
+	<T as FromFrameRef<'a>>::Error: Into<DecodeError>,
 {
-	type Error = std::io::Error;
+	type Error = DecodeError;
 
-	fn parse_frame(frame: &mut Bytes) -> Result<Self, Self::Error> {
-		Ok((T1::parse_frame(frame).map_err(Into::into)?, T2::parse_frame(frame).map_err(Into::into)?, T3::parse_frame(frame).map_err(Into::into)?, T4::parse_frame(frame).map_err(Into::into)?, T5::parse_frame(frame).map_err(Into::into)?, T6::parse_frame(frame).map_err(Into::into)?, T7::parse_frame(frame).map_err(Into::into)?, T8::parse_frame(frame).map_err(Into::into)?))
+	fn parse_frame_ref(frame: &mut &'a [u8]) -> Result<Self, Self::Error> {
+		utils::get_array(frame, "vecdeque", |frame| <T as FromFrameRef<'a>>::parse_frame_ref(frame)).map(VecDeque::from)
+	}
+}
+
+impl<'a, T> FromFrameRef<'a> for LinkedList<T>
+where
+	T: FromFrameRef<'a>,
+	<T as FromFrameRef<'a>>::Error: Into<DecodeError>,
+{
+	type Error = DecodeError;
+
+	fn parse_frame_ref(frame: &mut &'a [u8]) -> Result<Self, Self::Error> {
+		utils::get_array(frame, "linkedlist", |frame| <T as FromFrameRef<'a>>::parse_frame_ref(frame)).map(LinkedList::from_iter)
+	}
+}
+
+impl<'a, T> FromFrameRef<'a> for BinaryHeap<T>
+where
+	T: FromFrameRef<'a> + Ord,
+	<T as FromFrameRef<'a>>::Error: Into<DecodeError>,
+{
+	type Error = DecodeError;
+
+	fn parse_frame_ref(frame: &mut &'a [u8]) -> Result<Self, Self::Error> {
+		utils::get_array(frame, "binaryheap", |frame| <T as FromFrameRef<'a>>::parse_frame_ref(frame)).map(BinaryHeap::from)
+	}
+}
+
+impl<'a, T, const N: usize> FromFrameRef<'a> for [T; N]
+where
+	T: FromFrameRef<'a>,
+	<T as FromFrameRef<'a>>::Error: Into<DecodeError>,
+{
+	type Error = DecodeError;
+
+	fn parse_frame_ref(frame: &mut &'a [u8]) -> Result<Self, Self::Error> {
+		T::parse_array_ref(frame)
+	}
+}
+
+impl<'a, T: FromFrameRef<'a>> FromFrameRef<'a> for Box<T> {
+	type Error = <T as FromFrameRef<'a>>::Error;
+
+	fn parse_frame_ref(frame: &mut &'a [u8]) -> Result<Self, Self::Error> {
+		<T as FromFrameRef<'a>>::parse_frame_ref(frame).map(Box::new)
+	}
+}
+
+impl<'a, T: FromFrameRef<'a>> FromFrameRef<'a> for Rc<T> {
+	type Error = <T as FromFrameRef<'a>>::Error;
+
+	fn parse_frame_ref(frame: &mut &'a [u8]) -> Result<Self, Self::Error> {
+		<T as FromFrameRef<'a>>::parse_frame_ref(frame).map(Rc::new)
+	}
+}
+
+impl<'a, T: FromFrameRef<'a>> FromFrameRef<'a> for Arc<T> {
+	type Error = <T as FromFrameRef<'a>>::Error;
+
+	fn parse_frame_ref(frame: &mut &'a [u8]) -> Result<Self, Self::Error> {
+		<T as FromFrameRef<'a>>::parse_frame_ref(frame).map(Arc::new)
+	}
+}
+
+impl<'a, T: FromFrameRef<'a>> FromFrameRef<'a> for Wrapping<T> {
+	type Error = <T as FromFrameRef<'a>>::Error;
+
+	fn parse_frame_ref(frame: &mut &'a [u8]) -> Result<Self, Self::Error> {
+		<T as FromFrameRef<'a>>::parse_frame_ref(frame).map(Wrapping)
+	}
+}
+
+impl<'a, T: FromFrameRef<'a>> FromFrameRef<'a> for Reverse<T> {
+	type Error = <T as FromFrameRef<'a>>::Error;
+
+	fn parse_frame_ref(frame: &mut &'a [u8]) -> Result<Self, Self::Error> {
+		<T as FromFrameRef<'a>>::parse_frame_ref(frame).map(Reverse)
+	}
+}
+
+impl<'a, T> FromFrameRef<'a> for Cow<'a, T>
+where
+	T: ToOwned + ?Sized,
+	&'a T: FromFrameRef<'a>,
+{
+	type Error = <&'a T as FromFrameRef<'a>>::Error;
+
+	fn parse_frame_ref(frame: &mut &'a [u8]) -> Result<Self, Self::Error> {
+		<&'a T as FromFrameRef<'a>>::parse_frame_ref(frame).map(Cow::Borrowed)
+	}
+}
+
+impl<'a, T: ?Sized> FromFrameRef<'a> for PhantomData<T> {
+	type Error = DecodeError;
+
+	fn parse_frame_ref(_frame: &mut &'a [u8]) -> Result<Self, Self::Error> {
+		Ok(PhantomData)
+	}
+}
+
+impl IntoFrame for bool {
+	fn extend_frame(&self, frame: &mut BytesMut) {
+		frame.put_u8(*self as u8)
+	}
+
+	fn encoded_len(&self) -> usize {
+		std::mem::size_of::<Self>()
+	}
+}
+
+impl IntoFrame for u8 {
+	fn extend_frame(&self, frame: &mut BytesMut) {
+		frame.put_u8(*self)
+	}
+
+	fn encoded_len(&self) -> usize {
+		std::mem::size_of::<Self>()
+	}
+
+	fn try_extend_frame_slice(values: &[Self], frame: &mut BytesMut) -> Result<(), EncodeError> {
+		frame.put_slice(values);
+		Ok(())
+	}
+
+	fn encoded_len_slice(values: &[Self]) -> usize {
+		values.len()
+	}
+}
+
+impl IntoFrame for u16 {
+	fn extend_frame(&self, frame: &mut BytesMut) {
+		frame.put_u16(*self)
+	}
+
+	fn encoded_len(&self) -> usize {
+		std::mem::size_of::<Self>()
+	}
+}
+
+impl IntoFrame for u32 {
+	fn extend_frame(&self, frame: &mut BytesMut) {
+		frame.put_u32(*self)
+	}
+
+	fn encoded_len(&self) -> usize {
+		std::mem::size_of::<Self>()
+	}
+}
+
+impl IntoFrame for u64 {
+	fn extend_frame(&self, frame: &mut BytesMut) {
+		frame.put_u64(*self)
+	}
+
+	fn encoded_len(&self) -> usize {
+		std::mem::size_of::<Self>()
+	}
+}
+
+impl IntoFrame for i8 {
+	fn extend_frame(&self, frame: &mut BytesMut) {
+		frame.put_i8(*self)
+	}
+
+	fn encoded_len(&self) -> usize {
+		std::mem::size_of::<Self>()
+	}
+}
+
+impl IntoFrame for i16 {
+	fn extend_frame(&self, frame: &mut BytesMut) {
+		frame.put_i16(*self)
+	}
+
+	fn encoded_len(&self) -> usize {
+		std::mem::size_of::<Self>()
+	}
+}
+
+impl IntoFrame for i32 {
+	fn extend_frame(&self, frame: &mut BytesMut) {
+		frame.put_i32(*self)
+	}
+
+	fn encoded_len(&self) -> usize {
+		std::mem::size_of::<Self>()
+	}
+}
+
+impl IntoFrame for i64 {
+	fn extend_frame(&self, frame: &mut BytesMut) {
+		frame.put_i64(*self)
+	}
+
+	fn encoded_len(&self) -> usize {
+		std::mem::size_of::<Self>()
+	}
+}
+
+impl IntoFrame for u128 {
+	fn extend_frame(&self, frame: &mut BytesMut) {
+		frame.put_u128(*self)
+	}
+
+	fn encoded_len(&self) -> usize {
+		std::mem::size_of::<Self>()
+	}
+}
+
+impl IntoFrame for i128 {
+	fn extend_frame(&self, frame: &mut BytesMut) {
+		frame.put_i128(*self)
+	}
+
+	fn encoded_len(&self) -> usize {
+		std::mem::size_of::<Self>()
+	}
+}
+
+impl IntoFrame for usize {
+	fn extend_frame(&self, frame: &mut BytesMut) {
+		frame.put_u64(*self as u64)
+	}
+
+	fn size_hint(&self) -> usize {
+		self.encoded_len()
+	}
+
+	fn encoded_len(&self) -> usize {
+		8
+	}
+}
+
+impl IntoFrame for isize {
+	fn extend_frame(&self, frame: &mut BytesMut) {
+		frame.put_i64(*self as i64)
+	}
+
+	fn size_hint(&self) -> usize {
+		self.encoded_len()
+	}
+
+	fn encoded_len(&self) -> usize {
+		8
+	}
+}
+
+impl IntoFrame for f32 {
+	fn extend_frame(&self, frame: &mut BytesMut) {
+		frame.put_f32(*self)
+	}
+
+	fn encoded_len(&self) -> usize {
+		std::mem::size_of::<Self>()
+	}
+}
+
+impl IntoFrame for f64 {
+	fn extend_frame(&self, frame: &mut BytesMut) {
+		frame.put_f64(*self)
+	}
+
+	fn encoded_len(&self) -> usize {
+		std::mem::size_of::<Self>()
+	}
+}
+
+impl IntoFrame for char {
+	fn extend_frame(&self, frame: &mut BytesMut) {
+		frame.put_u32(*self as u32)
+	}
+
+	fn encoded_len(&self) -> usize {
+		std::mem::size_of::<Self>()
+	}
+}
+
+impl IntoFrame for &str {
+	fn extend_frame(&self, frame: &mut BytesMut) {
+		utils::expect_encoded(self.try_extend_frame(frame));
+	}
+
+	fn try_extend_frame(&self, frame: &mut BytesMut) -> Result<(), EncodeError> {
+		utils::try_put_str_with_prefix(frame, self, LengthPrefix::U32)
+	}
+
+	fn size_hint(&self) -> usize {
+		self.encoded_len()
+	}
+
+	fn encoded_len(&self) -> usize {
+		4 + self.len()
+	}
+}
+
+impl IntoFrame for String {
+	fn extend_frame(&self, frame: &mut BytesMut) {
+		utils::expect_encoded(self.try_extend_frame(frame));
+	}
+
+	fn try_extend_frame(&self, frame: &mut BytesMut) -> Result<(), EncodeError> {
+		<&str as IntoFrame>::try_extend_frame(&self.as_str(), frame)
+	}
+
+	fn size_hint(&self) -> usize {
+		self.encoded_len()
+	}
+
+	fn encoded_len(&self) -> usize {
+		<&str as IntoFrame>::encoded_len(&self.as_str())
+	}
+}
+
+impl IntoFrame for BytesStr {
+	fn extend_frame(&self, frame: &mut BytesMut) {
+		utils::expect_encoded(self.try_extend_frame(frame));
+	}
+
+	fn try_extend_frame(&self, frame: &mut BytesMut) -> Result<(), EncodeError> {
+		<&str as IntoFrame>::try_extend_frame(&self.as_str(), frame)
+	}
+
+	fn size_hint(&self) -> usize {
+		self.encoded_len()
+	}
+
+	fn encoded_len(&self) -> usize {
+		<&str as IntoFrame>::encoded_len(&self.as_str())
+	}
+}
+
+impl IntoFrame for &[u8] {
+	fn extend_frame(&self, frame: &mut BytesMut) {
+		utils::expect_encoded(self.try_extend_frame(frame));
+	}
+
+	fn try_extend_frame(&self, frame: &mut BytesMut) -> Result<(), EncodeError> {
+		utils::try_put_length(frame, self.len(), LengthPrefix::U32)?;
+		frame.put_slice(self);
+		Ok(())
+	}
+
+	fn size_hint(&self) -> usize {
+		self.encoded_len()
+	}
+
+	fn encoded_len(&self) -> usize {
+		4 + self.len()
+	}
+}
+
+impl IntoFrame for Bytes {
+	fn extend_frame(&self, frame: &mut BytesMut) {
+		utils::expect_encoded(self.try_extend_frame(frame));
+	}
+
+	fn try_extend_frame(&self, frame: &mut BytesMut) -> Result<(), EncodeError> {
+		<&[u8] as IntoFrame>::try_extend_frame(&self.as_ref(), frame)
+	}
+
+	fn size_hint(&self) -> usize {
+		self.encoded_len()
+	}
+
+	fn encoded_len(&self) -> usize {
+		<&[u8] as IntoFrame>::encoded_len(&self.as_ref())
+	}
+}
+
+impl<T: IntoFrame> IntoFrame for Option<T> {
+	fn extend_frame(&self, frame: &mut BytesMut) {
+		utils::expect_encoded(self.try_extend_frame(frame));
+	}
+
+	fn try_extend_frame(&self, frame: &mut BytesMut) -> Result<(), EncodeError> {
+		utils::try_put_option(frame, self, |frame, value| <T as IntoFrame>::try_extend_frame(value, frame))
+	}
+
+	fn size_hint(&self) -> usize {
+		self.encoded_len()
+	}
+
+	fn encoded_len(&self) -> usize {
+		1 + self.as_ref().map(|value| value.encoded_len()).unwrap_or(0)
+	}
+}
+
+impl<T: IntoFrame, E: IntoFrame> IntoFrame for Result<T, E> {
+	fn extend_frame(&self, frame: &mut BytesMut) {
+		utils::expect_encoded(self.try_extend_frame(frame));
+	}
+
+	fn try_extend_frame(&self, frame: &mut BytesMut) -> Result<(), EncodeError> {
+		utils::try_put_result(frame, self, |frame, value| <T as IntoFrame>::try_extend_frame(value, frame), |frame, value| <E as IntoFrame>::try_extend_frame(value, frame))
+	}
+
+	fn size_hint(&self) -> usize {
+		self.encoded_len()
+	}
+
+	fn encoded_len(&self) -> usize {
+		1 + match self {
+			Ok(value) => value.encoded_len(),
+			Err(value) => value.encoded_len(),
+		}
+	}
+}
+
+impl<T: IntoFrame> IntoFrame for Vec<T> {
+	fn extend_frame(&self, frame: &mut BytesMut) {
+		utils::expect_encoded(self.try_extend_frame(frame));
+	}
+
+	fn try_extend_frame(&self, frame: &mut BytesMut) -> Result<(), EncodeError> {
+		utils::try_put_length(frame, self.len(), LengthPrefix::U32)?;
+		T::try_extend_frame_slice(self, frame)
+	}
+
+	fn size_hint(&self) -> usize {
+		self.encoded_len()
+	}
+
+	fn encoded_len(&self) -> usize {
+		4 + T::encoded_len_slice(self)
+	}
+}
+
+impl<T: IntoFrame, const N: usize> IntoFrame for [T; N] {
+	fn extend_frame(&self, frame: &mut BytesMut) {
+		utils::expect_encoded(self.try_extend_frame(frame));
+	}
+
+	fn try_extend_frame(&self, frame: &mut BytesMut) -> Result<(), EncodeError> {
+		T::try_extend_frame_slice(self, frame)
+	}
+
+	fn size_hint(&self) -> usize {
+		self.encoded_len()
+	}
+
+	fn encoded_len(&self) -> usize {
+		T::encoded_len_slice(self)
+	}
+}
+
+impl<T: IntoFrame> IntoFrame for &T {
+	fn extend_frame(&self, frame: &mut BytesMut) {
+		utils::expect_encoded(self.try_extend_frame(frame));
+	}
+
+	fn try_extend_frame(&self, frame: &mut BytesMut) -> Result<(), EncodeError> {
+		(**self).try_extend_frame(frame)
+	}
+
+	fn size_hint(&self) -> usize {
+		self.encoded_len()
+	}
+
+	fn encoded_len(&self) -> usize {
+		(**self).encoded_len()
+	}
+}
+
+impl<T: IntoFrame> IntoFrame for Box<T> {
+	fn extend_frame(&self, frame: &mut BytesMut) {
+		utils::expect_encoded(self.try_extend_frame(frame));
+	}
+
+	fn try_extend_frame(&self, frame: &mut BytesMut) -> Result<(), EncodeError> {
+		(**self).try_extend_frame(frame)
+	}
+
+	fn size_hint(&self) -> usize {
+		self.encoded_len()
+	}
+
+	fn encoded_len(&self) -> usize {
+		(**self).encoded_len()
+	}
+}
+
+impl<T: IntoFrame> IntoFrame for Rc<T> {
+	fn extend_frame(&self, frame: &mut BytesMut) {
+		utils::expect_encoded(self.try_extend_frame(frame));
+	}
+
+	fn try_extend_frame(&self, frame: &mut BytesMut) -> Result<(), EncodeError> {
+		(**self).try_extend_frame(frame)
+	}
+
+	fn size_hint(&self) -> usize {
+		self.encoded_len()
+	}
+
+	fn encoded_len(&self) -> usize {
+		(**self).encoded_len()
+	}
+}
+
+impl<T: IntoFrame> IntoFrame for Arc<T> {
+	fn extend_frame(&self, frame: &mut BytesMut) {
+		utils::expect_encoded(self.try_extend_frame(frame));
+	}
+
+	fn try_extend_frame(&self, frame: &mut BytesMut) -> Result<(), EncodeError> {
+		(**self).try_extend_frame(frame)
+	}
+
+	fn size_hint(&self) -> usize {
+		self.encoded_len()
+	}
+
+	fn encoded_len(&self) -> usize {
+		(**self).encoded_len()
+	}
+}
+
+impl<T> IntoFrame for Cow<'_, T>
+where
+	T: ToOwned + ?Sized,
+	for<'b> &'b T: IntoFrame,
+{
+	fn extend_frame(&self, frame: &mut BytesMut) {
+		utils::expect_encoded(self.try_extend_frame(frame));
+	}
+
+	fn try_extend_frame(&self, frame: &mut BytesMut) -> Result<(), EncodeError> {
+		self.as_ref().try_extend_frame(frame)
+	}
+
+	fn size_hint(&self) -> usize {
+		self.encoded_len()
+	}
+
+	fn encoded_len(&self) -> usize {
+		self.as_ref().encoded_len()
+	}
+}
+
+impl<T: IntoFrame> IntoFrame for Wrapping<T> {
+	fn extend_frame(&self, frame: &mut BytesMut) {
+		utils::expect_encoded(self.try_extend_frame(frame));
+	}
+
+	fn try_extend_frame(&self, frame: &mut BytesMut) -> Result<(), EncodeError> {
+		self.0.try_extend_frame(frame)
+	}
+
+	fn size_hint(&self) -> usize {
+		self.encoded_len()
+	}
+
+	fn encoded_len(&self) -> usize {
+		self.0.encoded_len()
+	}
+}
+
+impl<T: IntoFrame> IntoFrame for Reverse<T> {
+	fn extend_frame(&self, frame: &mut BytesMut) {
+		utils::expect_encoded(self.try_extend_frame(frame));
+	}
+
+	fn try_extend_frame(&self, frame: &mut BytesMut) -> Result<(), EncodeError> {
+		self.0.try_extend_frame(frame)
+	}
+
+	fn size_hint(&self) -> usize {
+		self.encoded_len()
+	}
+
+	fn encoded_len(&self) -> usize {
+		self.0.encoded_len()
+	}
+}
+
+impl<T: ?Sized> IntoFrame for PhantomData<T> {
+	fn extend_frame(&self, _frame: &mut BytesMut) {}
+
+	fn size_hint(&self) -> usize {
+		0
+	}
+
+	fn encoded_len(&self) -> usize {
+		0
+	}
+}
+
+impl<T: IntoFrame, S> IntoFrame for HashSet<T, S> {
+	fn extend_frame(&self, frame: &mut BytesMut) {
+		utils::expect_encoded(self.try_extend_frame(frame));
+	}
+
+	fn try_extend_frame(&self, frame: &mut BytesMut) -> Result<(), EncodeError> {
+		utils::try_put_iter_with_prefix(frame, self.iter(), LengthPrefix::U32, |frame, value| <T as IntoFrame>::try_extend_frame(value, frame))
+	}
+
+	fn size_hint(&self) -> usize {
+		self.encoded_len()
+	}
+
+	fn encoded_len(&self) -> usize {
+		4 + self.iter().map(|value| value.encoded_len()).sum::<usize>()
+	}
+}
+
+impl<T: IntoFrame> IntoFrame for BTreeSet<T> {
+	fn extend_frame(&self, frame: &mut BytesMut) {
+		utils::expect_encoded(self.try_extend_frame(frame));
+	}
+
+	fn try_extend_frame(&self, frame: &mut BytesMut) -> Result<(), EncodeError> {
+		utils::try_put_iter_with_prefix(frame, self.iter(), LengthPrefix::U32, |frame, value| <T as IntoFrame>::try_extend_frame(value, frame))
+	}
+
+	fn size_hint(&self) -> usize {
+		self.encoded_len()
+	}
+
+	fn encoded_len(&self) -> usize {
+		4 + self.iter().map(|value| value.encoded_len()).sum::<usize>()
+	}
+}
+
+impl<T: IntoFrame> IntoFrame for VecDeque<T> {
+	fn extend_frame(&self, frame: &mut BytesMut) {
+		utils::expect_encoded(self.try_extend_frame(frame));
+	}
+
+	fn try_extend_frame(&self, frame: &mut BytesMut) -> Result<(), EncodeError> {
+		let (front, back) = self.as_slices();
+		utils::try_put_length(frame, self.len(), LengthPrefix::U32)?;
+		T::try_extend_frame_slice(front, frame)?;
+		T::try_extend_frame_slice(back, frame)
+	}
+
+	fn size_hint(&self) -> usize {
+		self.encoded_len()
+	}
+
+	fn encoded_len(&self) -> usize {
+		let (front, back) = self.as_slices();
+		4 + T::encoded_len_slice(front) + T::encoded_len_slice(back)
+	}
+}
+
+impl<T: IntoFrame> IntoFrame for LinkedList<T> {
+	fn extend_frame(&self, frame: &mut BytesMut) {
+		utils::expect_encoded(self.try_extend_frame(frame));
+	}
+
+	fn try_extend_frame(&self, frame: &mut BytesMut) -> Result<(), EncodeError> {
+		utils::try_put_iter_with_prefix(frame, self.iter(), LengthPrefix::U32, |frame, value| <T as IntoFrame>::try_extend_frame(value, frame))
+	}
+
+	fn size_hint(&self) -> usize {
+		self.encoded_len()
+	}
+
+	fn encoded_len(&self) -> usize {
+		4 + self.iter().map(|value| value.encoded_len()).sum::<usize>()
+	}
+}
+
+impl<T: IntoFrame> IntoFrame for BinaryHeap<T> {
+	fn extend_frame(&self, frame: &mut BytesMut) {
+		utils::expect_encoded(self.try_extend_frame(frame));
+	}
+
+	fn try_extend_frame(&self, frame: &mut BytesMut) -> Result<(), EncodeError> {
+		utils::try_put_iter_with_prefix(frame, self.iter(), LengthPrefix::U32, |frame, value| <T as IntoFrame>::try_extend_frame(value, frame))
+	}
+
+	fn size_hint(&self) -> usize {
+		self.encoded_len()
+	}
+
+	fn encoded_len(&self) -> usize {
+		4 + self.iter().map(|value| value.encoded_len()).sum::<usize>()
+	}
+}
+
+impl<K: IntoFrame, V: IntoFrame, S> IntoFrame for HashMap<K, V, S> {
+	fn extend_frame(&self, frame: &mut BytesMut) {
+		utils::expect_encoded(self.try_extend_frame(frame));
+	}
+
+	fn try_extend_frame(&self, frame: &mut BytesMut) -> Result<(), EncodeError> {
+		utils::try_put_map_with_prefix(frame, self.iter(), LengthPrefix::U32, |frame, key| <K as IntoFrame>::try_extend_frame(key, frame), |frame, value| <V as IntoFrame>::try_extend_frame(value, frame))
+	}
+
+	fn size_hint(&self) -> usize {
+		self.encoded_len()
+	}
+
+	fn encoded_len(&self) -> usize {
+		4 + self.iter().map(|(key, value)| key.encoded_len() + value.encoded_len()).sum::<usize>()
+	}
+}
+
+impl<K: IntoFrame, V: IntoFrame> IntoFrame for BTreeMap<K, V> {
+	fn extend_frame(&self, frame: &mut BytesMut) {
+		utils::expect_encoded(self.try_extend_frame(frame));
+	}
+
+	fn try_extend_frame(&self, frame: &mut BytesMut) -> Result<(), EncodeError> {
+		utils::try_put_map_with_prefix(frame, self.iter(), LengthPrefix::U32, |frame, key| <K as IntoFrame>::try_extend_frame(key, frame), |frame, value| <V as IntoFrame>::try_extend_frame(value, frame))
+	}
+
+	fn size_hint(&self) -> usize {
+		self.encoded_len()
+	}
+
+	fn encoded_len(&self) -> usize {
+		4 + self.iter().map(|(key, value)| key.encoded_len() + value.encoded_len()).sum::<usize>()
+	}
+}
+
+impl<K: IntoFrame, V: IntoFrame, S> IntoFrame for Canonical<HashMap<K, V, S>> {
+	fn extend_frame(&self, frame: &mut BytesMut) {
+		utils::expect_encoded(self.try_extend_frame(frame));
+	}
+
+	fn try_extend_frame(&self, frame: &mut BytesMut) -> Result<(), EncodeError> {
+		utils::try_put_map_canonical_with_prefix(frame, self.0.iter(), LengthPrefix::U32, |frame, key| <K as IntoFrame>::try_extend_frame(key, frame), |frame, value| <V as IntoFrame>::try_extend_frame(value, frame))
+	}
+
+	fn size_hint(&self) -> usize {
+		self.encoded_len()
+	}
+
+	fn encoded_len(&self) -> usize {
+		4 + self.0.iter().map(|(key, value)| key.encoded_len() + value.encoded_len()).sum::<usize>()
+	}
+}
+
+impl<T: IntoFrame, S> IntoFrame for Canonical<HashSet<T, S>> {
+	fn extend_frame(&self, frame: &mut BytesMut) {
+		utils::expect_encoded(self.try_extend_frame(frame));
+	}
+
+	fn try_extend_frame(&self, frame: &mut BytesMut) -> Result<(), EncodeError> {
+		utils::try_put_set_canonical_with_prefix(frame, self.0.iter(), LengthPrefix::U32, |frame, value| <T as IntoFrame>::try_extend_frame(value, frame))
+	}
+
+	fn size_hint(&self) -> usize {
+		self.encoded_len()
+	}
+
+	fn encoded_len(&self) -> usize {
+		4 + self.0.iter().map(|value| value.encoded_len()).sum::<usize>()
+	}
+}
+
+impl FromFrame for () {
+	type Error = DecodeError;
+
+	fn parse_frame(_frame: &mut Bytes) -> Result<Self, Self::Error> {
+		Ok(())
+	}
+}
+
+impl<'a> FromFrameRef<'a> for () {
+	type Error = DecodeError;
+
+	fn parse_frame_ref(_frame: &mut &'a [u8]) -> Result<Self, Self::Error> {
+		Ok(())
+	}
+}
+
+impl IntoFrame for () {
+	fn extend_frame(&self, _frame: &mut BytesMut) {}
+
+	fn size_hint(&self) -> usize {
+		0
+	}
+
+	fn encoded_len(&self) -> usize {
+		0
+	}
+}
+
+macro_rules! tuple_impls {
+	($(($($idx:tt $ty:ident),+)),+ $(,)?) => {
+		$(
+			impl<$($ty),+> FromFrame for ($($ty,)+)
+			where
+				$($ty: FromFrame, <$ty as FromFrame>::Error: Into<DecodeError>,)+
+			{
+				type Error = DecodeError;
+
+				fn parse_frame(frame: &mut Bytes) -> Result<Self, Self::Error> {
+					Ok(($(<$ty as FromFrame>::parse_frame(frame).map_err(|err| err.into().in_index($idx))?,)+))
+				}
+			}
+
+			impl<'a, $($ty),+> FromFrameRef<'a> for ($($ty,)+)
+			where
+				$($ty: FromFrameRef<'a>, <$ty as FromFrameRef<'a>>::Error: Into<DecodeError>,)+
+			{
+				type Error = DecodeError;
+
+				fn parse_frame_ref(frame: &mut &'a [u8]) -> Result<Self, Self::Error> {
+					Ok(($(<$ty as FromFrameRef<'a>>::parse_frame_ref(frame).map_err(|err| err.into().in_index($idx))?,)+))
+				}
+			}
+
+			impl<$($ty: IntoFrame),+> IntoFrame for ($($ty,)+) {
+				fn extend_frame(&self, frame: &mut BytesMut) {
+					utils::expect_encoded(self.try_extend_frame(frame));
+				}
+
+				fn try_extend_frame(&self, frame: &mut BytesMut) -> Result<(), EncodeError> {
+					$(self.$idx.try_extend_frame(frame)?;)+
+					Ok(())
+				}
+
+				fn size_hint(&self) -> usize {
+					self.encoded_len()
+				}
+
+				fn encoded_len(&self) -> usize {
+					0 $(+ self.$idx.encoded_len())+
+				}
+			}
+		)+
+	};
+}
+
+tuple_impls! {
+	(0 T1),
+	(0 T1, 1 T2),
+	(0 T1, 1 T2, 2 T3),
+	(0 T1, 1 T2, 2 T3, 3 T4),
+	(0 T1, 1 T2, 2 T3, 3 T4, 4 T5),
+	(0 T1, 1 T2, 2 T3, 3 T4, 4 T5, 5 T6),
+	(0 T1, 1 T2, 2 T3, 3 T4, 4 T5, 5 T6, 6 T7),
+	(0 T1, 1 T2, 2 T3, 3 T4, 4 T5, 5 T6, 6 T7, 7 T8),
+	(0 T1, 1 T2, 2 T3, 3 T4, 4 T5, 5 T6, 6 T7, 7 T8, 8 T9),
+	(0 T1, 1 T2, 2 T3, 3 T4, 4 T5, 5 T6, 6 T7, 7 T8, 8 T9, 9 T10),
+	(0 T1, 1 T2, 2 T3, 3 T4, 4 T5, 5 T6, 6 T7, 7 T8, 8 T9, 9 T10, 10 T11),
+	(0 T1, 1 T2, 2 T3, 3 T4, 4 T5, 5 T6, 6 T7, 7 T8, 8 T9, 9 T10, 10 T11, 11 T12),
+	(0 T1, 1 T2, 2 T3, 3 T4, 4 T5, 5 T6, 6 T7, 7 T8, 8 T9, 9 T10, 10 T11, 11 T12, 12 T13),
+	(0 T1, 1 T2, 2 T3, 3 T4, 4 T5, 5 T6, 6 T7, 7 T8, 8 T9, 9 T10, 10 T11, 11 T12, 12 T13, 13 T14),
+	(0 T1, 1 T2, 2 T3, 3 T4, 4 T5, 5 T6, 6 T7, 7 T8, 8 T9, 9 T10, 10 T11, 11 T12, 12 T13, 13 T14, 14 T15),
+	(0 T1, 1 T2, 2 T3, 3 T4, 4 T5, 5 T6, 6 T7, 7 T8, 8 T9, 9 T10, 10 T11, 11 T12, 12 T13, 13 T14, 14 T15, 15 T16),
+}
+
+impl<T: utils::LengthPrefixed + ?Sized> utils::LengthPrefixed for &T {
+	fn try_put_with_prefix(&self, dst: &mut BytesMut, prefix: LengthPrefix) -> Result<(), EncodeError> {
+		T::try_put_with_prefix(self, dst, prefix)
+	}
+
+	fn encoded_len_with_prefix(&self, prefix: LengthPrefix) -> usize {
+		T::encoded_len_with_prefix(self, prefix)
+	}
+}
+
+impl utils::LengthPrefixed for str {
+	fn try_put_with_prefix(&self, dst: &mut BytesMut, prefix: LengthPrefix) -> Result<(), EncodeError> {
+		utils::try_put_str_with_prefix(dst, self, prefix)
+	}
+
+	fn encoded_len_with_prefix(&self, prefix: LengthPrefix) -> usize {
+		utils::length_prefix_len(self.len(), prefix) + self.len()
+	}
+}
+
+impl utils::LengthPrefixed for String {
+	fn try_put_with_prefix(&self, dst: &mut BytesMut, prefix: LengthPrefix) -> Result<(), EncodeError> {
+		self.as_str().try_put_with_prefix(dst, prefix)
+	}
+
+	fn encoded_len_with_prefix(&self, prefix: LengthPrefix) -> usize {
+		self.as_str().encoded_len_with_prefix(prefix)
+	}
+}
+
+impl utils::LengthPrefixed for BytesStr {
+	fn try_put_with_prefix(&self, dst: &mut BytesMut, prefix: LengthPrefix) -> Result<(), EncodeError> {
+		self.as_str().try_put_with_prefix(dst, prefix)
+	}
+
+	fn encoded_len_with_prefix(&self, prefix: LengthPrefix) -> usize {
+		self.as_str().encoded_len_with_prefix(prefix)
+	}
+}
+
+impl<T: IntoFrame> utils::LengthPrefixed for [T] {
+	fn try_put_with_prefix(&self, dst: &mut BytesMut, prefix: LengthPrefix) -> Result<(), EncodeError> {
+		utils::try_put_length(dst, self.len(), prefix)?;
+		T::try_extend_frame_slice(self, dst)
+	}
+
+	fn encoded_len_with_prefix(&self, prefix: LengthPrefix) -> usize {
+		utils::length_prefix_len(self.len(), prefix) + T::encoded_len_slice(self)
+	}
+}
+
+impl<T: IntoFrame> utils::LengthPrefixed for Vec<T> {
+	fn try_put_with_prefix(&self, dst: &mut BytesMut, prefix: LengthPrefix) -> Result<(), EncodeError> {
+		self.as_slice().try_put_with_prefix(dst, prefix)
+	}
+
+	fn encoded_len_with_prefix(&self, prefix: LengthPrefix) -> usize {
+		self.as_slice().encoded_len_with_prefix(prefix)
+	}
+}
+
+impl<T: IntoFrame, S> utils::LengthPrefixed for HashSet<T, S> {
+	fn try_put_with_prefix(&self, dst: &mut BytesMut, prefix: LengthPrefix) -> Result<(), EncodeError> {
+		utils::try_put_iter_with_prefix(dst, self.iter(), prefix, |dst, value| <T as IntoFrame>::try_extend_frame(value, dst))
+	}
+
+	fn encoded_len_with_prefix(&self, prefix: LengthPrefix) -> usize {
+		utils::length_prefix_len(self.len(), prefix) + self.iter().map(|value| value.encoded_len()).sum::<usize>()
+	}
+}
+
+impl<T: IntoFrame> utils::LengthPrefixed for BTreeSet<T> {
+	fn try_put_with_prefix(&self, dst: &mut BytesMut, prefix: LengthPrefix) -> Result<(), EncodeError> {
+		utils::try_put_iter_with_prefix(dst, self.iter(), prefix, |dst, value| <T as IntoFrame>::try_extend_frame(value, dst))
+	}
+
+	fn encoded_len_with_prefix(&self, prefix: LengthPrefix) -> usize {
+		utils::length_prefix_len(self.len(), prefix) + self.iter().map(|value| value.encoded_len()).sum::<usize>()
+	}
+}
+
+impl<K: IntoFrame, V: IntoFrame, S> utils::LengthPrefixed for HashMap<K, V, S> {
+	fn try_put_with_prefix(&self, dst: &mut BytesMut, prefix: LengthPrefix) -> Result<(), EncodeError> {
+		utils::try_put_map_with_prefix(dst, self.iter(), prefix, |dst, key| <K as IntoFrame>::try_extend_frame(key, dst), |dst, value| <V as IntoFrame>::try_extend_frame(value, dst))
+	}
+
+	fn encoded_len_with_prefix(&self, prefix: LengthPrefix) -> usize {
+		utils::length_prefix_len(self.len(), prefix) + self.iter().map(|(key, value)| key.encoded_len() + value.encoded_len()).sum::<usize>()
+	}
+}
+
+impl<K: IntoFrame, V: IntoFrame> utils::LengthPrefixed for BTreeMap<K, V> {
+	fn try_put_with_prefix(&self, dst: &mut BytesMut, prefix: LengthPrefix) -> Result<(), EncodeError> {
+		utils::try_put_map_with_prefix(dst, self.iter(), prefix, |dst, key| <K as IntoFrame>::try_extend_frame(key, dst), |dst, value| <V as IntoFrame>::try_extend_frame(value, dst))
+	}
+
+	fn encoded_len_with_prefix(&self, prefix: LengthPrefix) -> usize {
+		utils::length_prefix_len(self.len(), prefix) + self.iter().map(|(key, value)| key.encoded_len() + value.encoded_len()).sum::<usize>()
+	}
+}
+
+impl<B: Buf> utils::FromLengthPrefixed<B> for String {
+	fn get_with_prefix(src: &mut B, name: &str, prefix: LengthPrefix) -> Result<Self, DecodeError> {
+		utils::get_string_with_prefix(src, name, prefix)
+	}
+}
+
+impl utils::FromLengthPrefixed<Bytes> for BytesStr {
+	fn get_with_prefix(src: &mut Bytes, name: &str, prefix: LengthPrefix) -> Result<Self, DecodeError> {
+		utils::get_bytes_str_with_prefix(src, name, prefix)
+	}
+}
+
+impl<'a> utils::FromLengthPrefixed<&'a [u8]> for &'a str {
+	fn get_with_prefix(src: &mut &'a [u8], name: &str, prefix: LengthPrefix) -> Result<Self, DecodeError> {
+		utils::get_str_with_prefix(src, name, prefix)
+	}
+}
+
+impl<'a> utils::FromLengthPrefixed<&'a [u8]> for &'a [u8] {
+	fn get_with_prefix(src: &mut &'a [u8], name: &str, prefix: LengthPrefix) -> Result<Self, DecodeError> {
+		utils::get_slice_with_prefix(src, name, prefix)
+	}
+}
+
+impl<T> utils::FromLengthPrefixed<Bytes> for Vec<T>
+where
+	T: FromFrame,
+	<T as FromFrame>::Error: Into<DecodeError>,
+{
+	fn get_with_prefix(src: &mut Bytes, _name: &str, prefix: LengthPrefix) -> Result<Self, DecodeError> {
+		T::parse_vec(src, prefix)
+	}
+}
+
+impl<'a, T> utils::FromLengthPrefixed<&'a [u8]> for Vec<T>
+where
+	T: FromFrameRef<'a>,
+	<T as FromFrameRef<'a>>::Error: Into<DecodeError>,
+{
+	fn get_with_prefix(src: &mut &'a [u8], _name: &str, prefix: LengthPrefix) -> Result<Self, DecodeError> {
+		T::parse_vec_ref(src, prefix)
+	}
+}
+
+impl<T, S> utils::FromLengthPrefixed<Bytes> for HashSet<T, S>
+where
+	T: FromFrame + Eq + Hash,
+	<T as FromFrame>::Error: Into<DecodeError>,
+	S: BuildHasher + Default,
+{
+	fn get_with_prefix(src: &mut Bytes, name: &str, prefix: LengthPrefix) -> Result<Self, DecodeError> {
+		utils::get_set_with_prefix(src, name, prefix, |frame| <T as FromFrame>::parse_frame(frame))
+	}
+}
+
+impl<T> utils::FromLengthPrefixed<Bytes> for BTreeSet<T>
+where
+	T: FromFrame + Ord,
+	<T as FromFrame>::Error: Into<DecodeError>,
+{
+	fn get_with_prefix(src: &mut Bytes, name: &str, prefix: LengthPrefix) -> Result<Self, DecodeError> {
+		utils::get_set_with_prefix(src, name, prefix, |frame| <T as FromFrame>::parse_frame(frame))
+	}
+}
+
+impl<'a, T, S> utils::FromLengthPrefixed<&'a [u8]> for HashSet<T, S>
+where
+	T: FromFrameRef<'a> + Eq + Hash,
+	<T as FromFrameRef<'a>>::Error: Into<DecodeError>,
+	S: BuildHasher + Default,
+{
+	fn get_with_prefix(src: &mut &'a [u8], name: &str, prefix: LengthPrefix) -> Result<Self, DecodeError> {
+		utils::get_set_with_prefix(src, name, prefix, |frame| <T as FromFrameRef<'a>>::parse_frame_ref(frame))
+	}
+}
+
+impl<'a, T> utils::FromLengthPrefixed<&'a [u8]> for BTreeSet<T>
+where
+	T: FromFrameRef<'a> + Ord,
+	<T as FromFrameRef<'a>>::Error: Into<DecodeError>,
+{
+	fn get_with_prefix(src: &mut &'a [u8], name: &str, prefix: LengthPrefix) -> Result<Self, DecodeError> {
+		utils::get_set_with_prefix(src, name, prefix, |frame| <T as FromFrameRef<'a>>::parse_frame_ref(frame))
+	}
+}
+
+impl<K, V, S> utils::FromLengthPrefixed<Bytes> for HashMap<K, V, S>
+where
+	K: FromFrame + Eq + Hash,
+	V: FromFrame,
+	S: BuildHasher + Default,
+	<K as FromFrame>::Error: Into<DecodeError>,
+	<V as FromFrame>::Error: Into<DecodeError>,
+{
+	fn get_with_prefix(src: &mut Bytes, name: &str, prefix: LengthPrefix) -> Result<Self, DecodeError> {
+		utils::get_map_with_prefix(src, name, prefix, |frame| <K as FromFrame>::parse_frame(frame), |frame| <V as FromFrame>::parse_frame(frame))
+	}
+}
+
+impl<K, V> utils::FromLengthPrefixed<Bytes> for BTreeMap<K, V>
+where
+	K: FromFrame + Ord,
+	V: FromFrame,
+	<K as FromFrame>::Error: Into<DecodeError>,
+	<V as FromFrame>::Error: Into<DecodeError>,
+{
+	fn get_with_prefix(src: &mut Bytes, name: &str, prefix: LengthPrefix) -> Result<Self, DecodeError> {
+		utils::get_map_with_prefix(src, name, prefix, |frame| <K as FromFrame>::parse_frame(frame), |frame| <V as FromFrame>::parse_frame(frame))
+	}
+}
+
+impl<'a, K, V, S> utils::FromLengthPrefixed<&'a [u8]> for HashMap<K, V, S>
+where
+	K: FromFrameRef<'a> + Eq + Hash,
+	V: FromFrameRef<'a>,
+	S: BuildHasher + Default,
+	<K as FromFrameRef<'a>>::Error: Into<DecodeError>,
+	<V as FromFrameRef<'a>>::Error: Into<DecodeError>,
+{
+	fn get_with_prefix(src: &mut &'a [u8], name: &str, prefix: LengthPrefix) -> Result<Self, DecodeError> {
+		utils::get_map_with_prefix(src, name, prefix, |frame| <K as FromFrameRef<'a>>::parse_frame_ref(frame), |frame| <V as FromFrameRef<'a>>::parse_frame_ref(frame))
+	}
+}
+
+impl<'a, K, V> utils::FromLengthPrefixed<&'a [u8]> for BTreeMap<K, V>
+where
+	K: FromFrameRef<'a> + Ord,
+	V: FromFrameRef<'a>,
+	<K as FromFrameRef<'a>>::Error: Into<DecodeError>,
+	<V as FromFrameRef<'a>>::Error: Into<DecodeError>,
+{
+	fn get_with_prefix(src: &mut &'a [u8], name: &str, prefix: LengthPrefix) -> Result<Self, DecodeError> {
+		utils::get_map_with_prefix(src, name, prefix, |frame| <K as FromFrameRef<'a>>::parse_frame_ref(frame), |frame| <V as FromFrameRef<'a>>::parse_frame_ref(frame))
 	}
 }
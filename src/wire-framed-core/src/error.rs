@@ -0,0 +1,254 @@
+use std::{fmt, io::{self, ErrorKind}, str::Utf8Error};
+
+use crate::LengthPrefix;
+
+/// The reason a frame could not be decoded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DecodeErrorKind {
+    /// The frame ended before `name` could be read.
+    UnexpectedEof { name: String, needed: usize, available: usize },
+    /// The tag read for `name` does not match any known value.
+    InvalidTag { name: String, tag: u64 },
+    /// The value read for `name` is not valid for its type.
+    InvalidValue { name: String, reason: String },
+    /// The bytes read for `name` are not valid UTF-8.
+    InvalidUtf8 { name: String, source: Utf8Error },
+    /// The length read for `name` exceeds the allowed limit.
+    LengthLimitExceeded { name: String, length: usize, limit: usize },
+    /// The value is nested deeper than the allowed limit.
+    DepthLimitExceeded { limit: usize },
+    /// Decoding the frame would allocate more than the allowed number of bytes.
+    AllocationLimitExceeded { requested: usize, limit: usize },
+    /// A key read for `name` appeared more than once in the same map or set.
+    DuplicateKey { name: String },
+    /// The value was decoded but `count` bytes were left over in the frame.
+    TrailingBytes { count: usize },
+    /// Any other error, usually coming from a manual [`FromFrame`](crate::FromFrame) implementation.
+    Custom(String),
+}
+
+impl fmt::Display for DecodeErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnexpectedEof { name, needed, available } => write!(f, "expected '{}': needed {} bytes but only {} are left", name, needed, available),
+            Self::InvalidTag { name, tag } => write!(f, "invalid '{}' tag {}", name, tag),
+            Self::InvalidValue { name, reason } => write!(f, "invalid '{}': {}", name, reason),
+            Self::InvalidUtf8 { name, source } => write!(f, "'{}' is not a valid UTF-8 string: {}", name, source),
+            Self::LengthLimitExceeded { name, length, limit } => write!(f, "'{}' length {} exceeds the limit of {}", name, length, limit),
+            Self::DepthLimitExceeded { limit } => write!(f, "nesting depth exceeds the limit of {}", limit),
+            Self::AllocationLimitExceeded { requested, limit } => write!(f, "allocating {} bytes exceeds the limit of {}", requested, limit),
+            Self::DuplicateKey { name } => write!(f, "duplicate '{}' key", name),
+            Self::TrailingBytes { count } => write!(f, "{} trailing bytes left after decoding", count),
+            Self::Custom(msg) => f.write_str(msg),
+        }
+    }
+}
+
+/// A single step in the path to the value that failed to decode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PathSegment {
+    /// A named or positional struct field.
+    Field(&'static str),
+    /// An enum variant.
+    Variant(&'static str),
+    /// An element of a collection or tuple.
+    Index(usize),
+}
+
+/// Error returned when a frame cannot be decoded.
+///
+/// Besides the [`DecodeErrorKind`] it carries the path of fields leading to the failing value
+/// and the byte offset into the frame at which decoding failed. It converts into an [`io::Error`]
+/// of kind [`ErrorKind::InvalidInput`] and can be recovered from it again with [`DecodeError::from`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodeError {
+    kind: DecodeErrorKind,
+    path: Vec<PathSegment>,
+    remaining: Option<usize>,
+    offset: Option<usize>,
+}
+
+impl DecodeError {
+    /// Creates a new error with `remaining` bytes left in the frame at the point of failure.
+    pub fn new(kind: DecodeErrorKind, remaining: usize) -> Self {
+        Self {
+            kind,
+            path: Vec::new(),
+            remaining: Some(remaining),
+            offset: None,
+        }
+    }
+
+    /// Creates a new [`DecodeErrorKind::Custom`] error.
+    pub fn custom(msg: impl fmt::Display) -> Self {
+        Self {
+            kind: DecodeErrorKind::Custom(msg.to_string()),
+            path: Vec::new(),
+            remaining: None,
+            offset: None,
+        }
+    }
+
+    /// Returns the reason decoding failed.
+    pub fn kind(&self) -> &DecodeErrorKind {
+        &self.kind
+    }
+
+    /// Returns the path to the value that failed to decode, outermost segment first.
+    pub fn path(&self) -> &[PathSegment] {
+        &self.path
+    }
+
+    /// Returns the number of bytes that were left in the frame when decoding failed.
+    pub fn remaining(&self) -> Option<usize> {
+        self.remaining
+    }
+
+    /// Returns the byte offset into the frame at which decoding failed.
+    ///
+    /// The offset is known once the error has passed through [`FromFrame::from_frame`](crate::FromFrame::from_frame)
+    /// of a derived type or through [`DecodeError::with_frame_len`].
+    pub fn offset(&self) -> Option<usize> {
+        self.offset
+    }
+
+    /// Prepends a struct field to the path.
+    pub fn in_field(mut self, name: &'static str) -> Self {
+        self.path.insert(0, PathSegment::Field(name));
+        self
+    }
+
+    /// Prepends an enum variant to the path.
+    pub fn in_variant(mut self, name: &'static str) -> Self {
+        self.path.insert(0, PathSegment::Variant(name));
+        self
+    }
+
+    /// Prepends a collection or tuple index to the path.
+    pub fn in_index(mut self, index: usize) -> Self {
+        self.path.insert(0, PathSegment::Index(index));
+        self
+    }
+
+    /// Accounts for `trailing` bytes that followed the sub-slice of the frame the error was raised in.
+    pub fn with_trailing(mut self, trailing: usize) -> Self {
+        self.remaining = self.remaining.map(|remaining| remaining + trailing);
+        self
+    }
+
+    /// Resolves the byte offset of the error given the total length of the decoded frame.
+    pub fn with_frame_len(mut self, frame_len: usize) -> Self {
+        if self.offset.is_none() {
+            self.offset = self.remaining.map(|remaining| frame_len.saturating_sub(remaining));
+        }
+
+        self
+    }
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.path.is_empty() {
+            for (i, segment) in self.path.iter().enumerate() {
+                match segment {
+                    PathSegment::Field(name) | PathSegment::Variant(name) if i == 0 => f.write_str(name)?,
+                    PathSegment::Field(name) | PathSegment::Variant(name) => write!(f, ".{}", name)?,
+                    PathSegment::Index(index) => write!(f, "[{}]", index)?,
+                }
+            }
+
+            f.write_str(": ")?;
+        }
+
+        write!(f, "{}", self.kind)?;
+        if let Some(offset) = self.offset {
+            write!(f, " at byte {}", offset)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+impl From<DecodeError> for io::Error {
+    fn from(err: DecodeError) -> Self {
+        io::Error::new(ErrorKind::InvalidInput, err)
+    }
+}
+
+impl From<io::Error> for DecodeError {
+    fn from(err: io::Error) -> Self {
+        if err.get_ref().is_some_and(|inner| inner.is::<DecodeError>()) {
+            let inner = err.into_inner().expect("checked above");
+            return *inner.downcast::<DecodeError>().expect("checked above");
+        }
+
+        Self::custom(err)
+    }
+}
+
+impl From<std::convert::Infallible> for DecodeError {
+    fn from(err: std::convert::Infallible) -> Self {
+        match err {}
+    }
+}
+
+/// Error returned when a value cannot be encoded into a frame.
+///
+/// It converts into an [`io::Error`] of kind [`ErrorKind::InvalidInput`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum EncodeError {
+    /// A string or collection is longer than its length prefix can hold.
+    LengthOverflow { length: usize, prefix: LengthPrefix },
+    /// The value of `name` cannot be represented in the frame.
+    InvalidValue { name: String, reason: String },
+}
+
+impl fmt::Display for EncodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::LengthOverflow { length, prefix } => write!(f, "length {} does not fit into a {:?} length prefix", length, prefix),
+            Self::InvalidValue { name, reason } => write!(f, "cannot encode '{}': {}", name, reason),
+        }
+    }
+}
+
+impl std::error::Error for EncodeError {}
+
+impl From<EncodeError> for io::Error {
+    fn from(err: EncodeError) -> Self {
+        io::Error::new(ErrorKind::InvalidInput, err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_includes_path_and_offset() {
+        let err = DecodeError::new(DecodeErrorKind::UnexpectedEof { name: "string".into(), needed: 4, available: 1 }, 1)
+            .in_index(2)
+            .in_field("names")
+            .in_field("user")
+            .with_frame_len(10);
+
+        assert_eq!(err.path(), &[PathSegment::Field("user"), PathSegment::Field("names"), PathSegment::Index(2)]);
+        assert_eq!(err.offset(), Some(9));
+        assert_eq!(err.to_string(), "user.names[2]: expected 'string': needed 4 bytes but only 1 are left at byte 9");
+    }
+
+    #[test]
+    fn io_error_roundtrip() {
+        let err = DecodeError::new(DecodeErrorKind::InvalidTag { name: "option".into(), tag: 7 }, 3).in_field("a");
+        let io_err = io::Error::from(err.clone());
+        assert_eq!(io_err.kind(), ErrorKind::InvalidInput);
+        assert_eq!(DecodeError::from(io_err), err);
+
+        let other = DecodeError::from(io::Error::other("boom"));
+        assert_eq!(other.kind(), &DecodeErrorKind::Custom("boom".into()));
+    }
+}
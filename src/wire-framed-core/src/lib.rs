@@ -1,8 +1,16 @@
 pub mod codec;
+pub mod error;
+pub mod limits;
+pub mod types;
 pub mod utils;
 pub mod common_impls;
-pub use codec::{FrameCodec, Framed, FramedRead, FramedWrite};
+// Kept for compatibility, `common_impls` only holds trait implementations at the moment
+#[allow(unused_imports)]
 pub use common_impls::*;
+pub use codec::{ByteOrder, FrameCodec, FrameCodecBuilder, FrameTooLarge, LengthPrefix, Framed, FramedRead, FramedWrite, TypedCodecError, TypedFrameCodec, TypedFramed, TypedFramedRead, TypedFramedWrite};
+pub use error::{DecodeError, DecodeErrorKind, EncodeError, PathSegment};
+pub use limits::DecodeLimits;
+pub use types::{BytesStr, Canonical};
 pub use bytes;
 pub use tokio_util::codec as tokio_codec;
 
@@ -14,34 +22,208 @@ pub trait FromFrame: Sized {
     type Error;
 
     /// Parse a frame into a `Self`.
+    ///
+    /// This is the building block of the other methods and does not install any [`DecodeLimits`] itself.
+    /// Called on its own, outside of [`FromFrame::from_frame`] and the other entry points, every string and
+    /// collection is checked against the limits of the current thread separately, so the
+    /// [total allocation](DecodeLimits::max_allocation) of the frame is not capped. Wrap such calls in
+    /// [`limits::with_limits`] to decode them as one frame.
     fn parse_frame(frame: &mut Bytes) -> Result<Self, Self::Error>;
 
     /// Convert a frame into a `Self`.
-    fn from_frame(mut frame: Bytes) -> Result<Self, Self::Error> {
-        Self::parse_frame(&mut frame)
+    ///
+    /// Applies the [`DecodeLimits`] that are currently in effect, or the defaults.
+    fn from_frame(frame: Bytes) -> Result<Self, Self::Error> {
+        Self::from_frame_with_limits(frame, DecodeLimits::current())
+    }
+
+    /// Convert a frame into a `Self`, rejecting it if decoding exceeds `limits`.
+    fn from_frame_with_limits(mut frame: Bytes, limits: DecodeLimits) -> Result<Self, Self::Error> {
+        limits::with_limits(limits, || Self::parse_frame(&mut frame))
+    }
+
+    /// Convert a frame into a `Self`, failing with [`DecodeErrorKind::TrailingBytes`] if any bytes are left over.
+    fn from_frame_exact(frame: Bytes) -> Result<Self, Self::Error>
+    where
+        Self::Error: From<DecodeError>,
+    {
+        Self::from_frame_exact_with_limits(frame, DecodeLimits::current())
+    }
+
+    /// Convert a frame into a `Self` like [`FromFrame::from_frame_exact`], rejecting it if decoding exceeds `limits`.
+    fn from_frame_exact_with_limits(mut frame: Bytes, limits: DecodeLimits) -> Result<Self, Self::Error>
+    where
+        Self::Error: From<DecodeError>,
+    {
+        let value = limits::with_limits(limits, || Self::parse_frame(&mut frame))?;
+        utils::check_trailing(&frame)?;
+        Ok(value)
+    }
+
+    /// Parse `N` consecutive values into an array.
+    ///
+    /// Used by the `[T; N]` implementation, so types such as [`u8`] can read the whole array at once.
+    fn parse_array<const N: usize>(frame: &mut Bytes) -> Result<[Self; N], DecodeError>
+    where
+        Self::Error: Into<DecodeError>,
+    {
+        utils::get_fixed_array(frame, Self::parse_frame)
+    }
+
+    /// Parse a sequence of values with a length prefix of the given width into a [`Vec`].
+    ///
+    /// Used by the `Vec<T>` implementation, so types such as [`u8`] can read the whole sequence at once.
+    fn parse_vec(frame: &mut Bytes, prefix: LengthPrefix) -> Result<Vec<Self>, DecodeError>
+    where
+        Self::Error: Into<DecodeError>,
+    {
+        utils::get_array_with_prefix(frame, "array", prefix, Self::parse_frame)
+    }
+}
+
+/// Trait for converting a frame into a `Self` that borrows from the frame.
+///
+/// This allows decoding fields such as `&'a str` and `&'a [u8]` without copying them.
+pub trait FromFrameRef<'a>: Sized {
+    /// The error type returned when parsing a frame.
+    type Error;
+
+    /// Parse a frame into a `Self`.
+    ///
+    /// Like [`FromFrame::parse_frame`], this does not install any [`DecodeLimits`] itself, so the total
+    /// allocation of the frame is only capped inside [`FromFrameRef::from_frame_ref`] and the other entry points.
+    fn parse_frame_ref(frame: &mut &'a [u8]) -> Result<Self, Self::Error>;
+
+    /// Convert a frame into a `Self`.
+    ///
+    /// Applies the [`DecodeLimits`] that are currently in effect, or the defaults.
+    fn from_frame_ref(frame: &'a [u8]) -> Result<Self, Self::Error> {
+        Self::from_frame_ref_with_limits(frame, DecodeLimits::current())
+    }
+
+    /// Convert a frame into a `Self`, rejecting it if decoding exceeds `limits`.
+    fn from_frame_ref_with_limits(mut frame: &'a [u8], limits: DecodeLimits) -> Result<Self, Self::Error> {
+        limits::with_limits(limits, || Self::parse_frame_ref(&mut frame))
+    }
+
+    /// Convert a frame into a `Self`, failing with [`DecodeErrorKind::TrailingBytes`] if any bytes are left over.
+    fn from_frame_ref_exact(frame: &'a [u8]) -> Result<Self, Self::Error>
+    where
+        Self::Error: From<DecodeError>,
+    {
+        Self::from_frame_ref_exact_with_limits(frame, DecodeLimits::current())
+    }
+
+    /// Convert a frame into a `Self` like [`FromFrameRef::from_frame_ref_exact`], rejecting it if decoding exceeds `limits`.
+    fn from_frame_ref_exact_with_limits(mut frame: &'a [u8], limits: DecodeLimits) -> Result<Self, Self::Error>
+    where
+        Self::Error: From<DecodeError>,
+    {
+        let value = limits::with_limits(limits, || Self::parse_frame_ref(&mut frame))?;
+        utils::check_trailing(&frame)?;
+        Ok(value)
+    }
+
+    /// Parse `N` consecutive values into an array.
+    ///
+    /// Used by the `[T; N]` implementation, so types such as [`u8`] can read the whole array at once.
+    fn parse_array_ref<const N: usize>(frame: &mut &'a [u8]) -> Result<[Self; N], DecodeError>
+    where
+        Self::Error: Into<DecodeError>,
+    {
+        utils::get_fixed_array(frame, Self::parse_frame_ref)
+    }
+
+    /// Parse a sequence of values with a length prefix of the given width into a [`Vec`].
+    ///
+    /// Used by the `Vec<T>` implementation, so types such as [`u8`] can read the whole sequence at once.
+    fn parse_vec_ref(frame: &mut &'a [u8], prefix: LengthPrefix) -> Result<Vec<Self>, DecodeError>
+    where
+        Self::Error: Into<DecodeError>,
+    {
+        utils::get_array_with_prefix(frame, "array", prefix, Self::parse_frame_ref)
     }
 }
 
 /// Trait for converting a `Self` into a frame.
 pub trait IntoFrame: Sized {
     /// Extend a frame with the contents of `Self`.
+    ///
+    /// # Panics
+    /// Implementations panic if a value cannot be encoded, such as a string that is too long for its length prefix.
     fn extend_frame(&self, frame: &mut BytesMut);
 
+    /// Extend a frame with the contents of `Self`, returning an error instead of panicking if a value cannot be encoded.
+    ///
+    /// The default implementation calls [`IntoFrame::extend_frame`], which is right for types that always encode.
+    /// Types that write length prefixes or contain other values override it and propagate the errors of their
+    /// contents, usually implementing `extend_frame` with [`utils::expect_encoded`] on top of it.
+    /// On error, the frame holds whatever was written before the failing value.
+    fn try_extend_frame(&self, frame: &mut BytesMut) -> Result<(), EncodeError> {
+        self.extend_frame(frame);
+        Ok(())
+    }
+
     /// Returns the size hint of `Self` in bytes.
     fn size_hint(&self) -> usize {
         std::mem::size_of::<Self>()
     }
 
+    /// Returns the exact number of bytes [`IntoFrame::extend_frame`] writes.
+    ///
+    /// The default implementation encodes `Self` into a scratch buffer, so implementations
+    /// should override it whenever the length can be computed directly.
+    fn encoded_len(&self) -> usize {
+        let mut frame = BytesMut::new();
+        // A value that cannot be encoded fails again in `try_extend_frame`, where the error is returned
+        let _ = self.try_extend_frame(&mut frame);
+        frame.len()
+    }
+
     /// Converts `Self` into an owned frame.
+    ///
+    /// The frame is allocated once with the capacity from [`IntoFrame::encoded_len`].
+    ///
+    /// # Panics
+    /// Panics if a value cannot be encoded, such as a string that is too long for its length prefix.
+    /// Use [`IntoFrame::try_into_frame`] when the values are not under your control.
+    #[allow(clippy::wrong_self_convention)]
     fn into_frame(&self) -> Bytes {
-        let mut frame = BytesMut::with_capacity(self.size_hint());
-        self.extend_frame(&mut frame);
-        frame.into()
+        match self.try_into_frame() {
+            Ok(frame) => frame,
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    /// Converts `Self` into an owned frame, returning an error instead of panicking if a value cannot be encoded.
+    ///
+    /// The frame is allocated once with the capacity from [`IntoFrame::encoded_len`].
+    fn try_into_frame(&self) -> Result<Bytes, EncodeError> {
+        let len = self.encoded_len();
+        let mut frame = BytesMut::with_capacity(len);
+        self.try_extend_frame(&mut frame)?;
+        debug_assert_eq!(frame.len(), len, "`encoded_len` does not match the encoded frame");
+        Ok(frame.freeze())
+    }
+
+    /// Extend a frame with a slice of values, without a length prefix.
+    ///
+    /// Used by the `[T; N]`, `Vec<T>` and `VecDeque<T>` implementations, so types such as [`u8`] can write the whole slice at once.
+    fn try_extend_frame_slice(values: &[Self], frame: &mut BytesMut) -> Result<(), EncodeError> {
+        values.iter().try_for_each(|value| value.try_extend_frame(frame))
+    }
+
+    /// Returns the number of bytes [`IntoFrame::try_extend_frame_slice`] writes for `values`.
+    ///
+    /// Types with a fixed size should override it, so the length of a slice is known without visiting every element.
+    fn encoded_len_slice(values: &[Self]) -> usize {
+        values.iter().map(|value| value.encoded_len()).sum()
     }
 }
 
 
 #[cfg(test)]
+#[allow(non_local_definitions)]
 mod tests {
     use bytes::{BufMut, BytesMut};
     use super::*;
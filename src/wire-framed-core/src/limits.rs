@@ -0,0 +1,281 @@
+use std::cell::Cell;
+use bytes::Buf;
+
+use crate::{DecodeError, DecodeErrorKind};
+
+/// Resource limits applied while decoding a frame.
+///
+/// The limits guard against frames that announce huge collections or deeply nested values.
+/// They are installed for the duration of [`FromFrame::from_frame_with_limits`](crate::FromFrame::from_frame_with_limits)
+/// and every other `from_frame` entry point, including [`TypedFrameCodec`](crate::TypedFrameCodec), and
+/// picked up by the [`utils`](crate::utils) functions, the common implementations and the derived
+/// implementations. Outside of such a call the [`Default`] limits apply to every string and collection
+/// on its own, without a budget for the whole frame.
+///
+/// The installed limits belong to the current thread. Decoding is synchronous, so this only matters for
+/// a manual implementation that hands part of a frame to another thread, which has to install the limits
+/// there again with [`with_limits`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DecodeLimits {
+    /// The maximum number of elements in a single collection.
+    pub max_collection_length: usize,
+    /// The maximum length of a single string in bytes.
+    pub max_string_length: usize,
+    /// The maximum nesting depth of structs, enums and collections.
+    pub max_depth: usize,
+    /// The maximum number of bytes allocated for strings and collections while decoding one frame.
+    pub max_allocation: usize,
+}
+
+impl DecodeLimits {
+    /// Limits that never reject anything.
+    pub const UNLIMITED: Self = Self {
+        max_collection_length: usize::MAX,
+        max_string_length: usize::MAX,
+        max_depth: usize::MAX,
+        max_allocation: usize::MAX,
+    };
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the maximum number of elements in a single collection.
+    pub fn with_max_collection_length(mut self, max_collection_length: usize) -> Self {
+        self.max_collection_length = max_collection_length;
+        self
+    }
+
+    /// Sets the maximum length of a single string in bytes.
+    pub fn with_max_string_length(mut self, max_string_length: usize) -> Self {
+        self.max_string_length = max_string_length;
+        self
+    }
+
+    /// Sets the maximum nesting depth.
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Sets the maximum number of bytes allocated while decoding one frame.
+    pub fn with_max_allocation(mut self, max_allocation: usize) -> Self {
+        self.max_allocation = max_allocation;
+        self
+    }
+
+    /// Returns the limits of the frame currently being decoded on this thread, or the defaults.
+    pub fn current() -> Self {
+        STATE.with(|state| state.get().limits)
+    }
+
+    /// Returns the stricter of both limits for every setting.
+    fn tightest(self, other: Self) -> Self {
+        Self {
+            max_collection_length: self.max_collection_length.min(other.max_collection_length),
+            max_string_length: self.max_string_length.min(other.max_string_length),
+            max_depth: self.max_depth.min(other.max_depth),
+            max_allocation: self.max_allocation.min(other.max_allocation),
+        }
+    }
+}
+
+impl Default for DecodeLimits {
+    fn default() -> Self {
+        Self {
+            max_collection_length: 16 * 1024 * 1024,
+            max_string_length: 16 * 1024 * 1024,
+            max_depth: 128,
+            max_allocation: 256 * 1024 * 1024,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct State {
+    limits: DecodeLimits,
+    depth: usize,
+    allocated: usize,
+    /// Whether a [`with_limits`] scope is active. Allocations are only accumulated inside one.
+    scoped: bool,
+}
+
+thread_local! {
+    static STATE: Cell<State> = Cell::new(State {
+        limits: DecodeLimits::default(),
+        depth: 0,
+        allocated: 0,
+        scoped: false,
+    });
+}
+
+/// Restores the previous decoding state when dropped.
+///
+/// Allocations made in a nested scope stay charged to the enclosing one.
+struct Scope(State);
+
+impl Drop for Scope {
+    fn drop(&mut self) {
+        STATE.with(|state| {
+            let allocated = match self.0.scoped {
+                true => state.get().allocated,
+                false => self.0.allocated,
+            };
+            state.set(State { allocated, ..self.0 });
+        });
+    }
+}
+
+/// Runs `f` with `limits` installed.
+///
+/// The outermost call starts with fresh depth and allocation counters. Nested calls, such as a manual
+/// implementation decoding a sub-slice with [`FromFrame::from_frame`](crate::FromFrame::from_frame), keep
+/// counting against the enclosing frame and can only make its limits stricter.
+pub fn with_limits<R>(limits: DecodeLimits, f: impl FnOnce() -> R) -> R {
+    let previous = STATE.with(|state| {
+        let current = state.get();
+        state.replace(match current.scoped {
+            true => State { limits: current.limits.tightest(limits), ..current },
+            false => State { limits, depth: 0, allocated: 0, scoped: true },
+        })
+    });
+    let _scope = Scope(previous);
+    f()
+}
+
+/// Guard returned by [`enter`] that leaves the nesting level when dropped.
+#[must_use]
+#[derive(Debug)]
+pub struct DepthGuard(());
+
+impl Drop for DepthGuard {
+    fn drop(&mut self) {
+        STATE.with(|state| {
+            let mut current = state.get();
+            current.depth -= 1;
+            state.set(current);
+        });
+    }
+}
+
+/// Enters a nested value, failing if the maximum nesting depth is exceeded.
+pub fn enter(src: &impl Buf) -> Result<DepthGuard, DecodeError> {
+    STATE.with(|state| {
+        let mut current = state.get();
+        if current.depth >= current.limits.max_depth {
+            return Err(DecodeError::new(DecodeErrorKind::DepthLimitExceeded { limit: current.limits.max_depth }, src.remaining()));
+        }
+
+        current.depth += 1;
+        state.set(current);
+        Ok(DepthGuard(()))
+    })
+}
+
+/// Checks the announced length of a collection of `T` and charges its allocation.
+pub fn check_collection<T>(src: &impl Buf, name: &str, length: usize) -> Result<(), DecodeError> {
+    let limit = DecodeLimits::current().max_collection_length;
+    if length > limit {
+        let kind = DecodeErrorKind::LengthLimitExceeded { name: name.to_string(), length, limit };
+        return Err(DecodeError::new(kind, src.remaining()));
+    }
+
+    allocate(src, length.saturating_mul(std::mem::size_of::<T>()))
+}
+
+/// Checks the announced length of a string and charges its allocation.
+pub fn check_string(src: &impl Buf, name: &str, length: usize) -> Result<(), DecodeError> {
+    let limit = DecodeLimits::current().max_string_length;
+    if length > limit {
+        let kind = DecodeErrorKind::LengthLimitExceeded { name: name.to_string(), length, limit };
+        return Err(DecodeError::new(kind, src.remaining()));
+    }
+
+    allocate(src, length)
+}
+
+/// Charges `bytes` against the allocation budget of the frame being decoded.
+///
+/// Outside of [`with_limits`] there is no frame to charge, so only `bytes` itself is checked.
+pub fn allocate(src: &impl Buf, bytes: usize) -> Result<(), DecodeError> {
+    STATE.with(|state| {
+        let mut current = state.get();
+        let allocated = if current.scoped { current.allocated.saturating_add(bytes) } else { bytes };
+        if allocated > current.limits.max_allocation {
+            let kind = DecodeErrorKind::AllocationLimitExceeded { requested: allocated, limit: current.limits.max_allocation };
+            return Err(DecodeError::new(kind, src.remaining()));
+        }
+
+        current.allocated = allocated;
+        if current.scoped {
+            state.set(current);
+        }
+
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use super::*;
+
+    #[test]
+    fn limits_are_scoped() {
+        let limits = DecodeLimits::new().with_max_depth(1);
+        with_limits(limits, || {
+            assert_eq!(DecodeLimits::current(), limits);
+
+            let src = Bytes::new();
+            let guard = enter(&src).unwrap();
+            assert_eq!(enter(&src).unwrap_err().kind(), &DecodeErrorKind::DepthLimitExceeded { limit: 1 });
+            drop(guard);
+            assert!(enter(&src).is_ok());
+        });
+
+        assert_eq!(DecodeLimits::current(), DecodeLimits::default());
+    }
+
+    #[test]
+    fn allocation_budget() {
+        let src = Bytes::new();
+        with_limits(DecodeLimits::new().with_max_allocation(100), || {
+            check_string(&src, "a", 60).unwrap();
+            let err = check_collection::<u32>(&src, "b", 11).unwrap_err();
+            assert_eq!(err.kind(), &DecodeErrorKind::AllocationLimitExceeded { requested: 104, limit: 100 });
+        });
+    }
+
+    #[test]
+    fn nested_scopes_share_counters() {
+        let src = Bytes::new();
+        with_limits(DecodeLimits::new().with_max_depth(2).with_max_allocation(100), || {
+            let _guard = enter(&src).unwrap();
+            check_string(&src, "a", 60).unwrap();
+
+            with_limits(DecodeLimits::UNLIMITED, || {
+                assert_eq!(DecodeLimits::current().max_depth, 2);
+                let _guard = enter(&src).unwrap();
+                assert!(enter(&src).is_err());
+                check_string(&src, "b", 30).unwrap();
+            });
+
+            let err = check_string(&src, "c", 20).unwrap_err();
+            assert_eq!(err.kind(), &DecodeErrorKind::AllocationLimitExceeded { requested: 110, limit: 100 });
+            assert!(enter(&src).is_ok());
+        });
+
+        with_limits(DecodeLimits::new().with_max_allocation(100), || check_string(&src, "a", 100).unwrap());
+    }
+
+    #[test]
+    fn unscoped_allocations_do_not_accumulate() {
+        let src = Bytes::new();
+        let limit = DecodeLimits::default().max_allocation;
+        for _ in 0..4 {
+            allocate(&src, limit / 2).unwrap();
+        }
+
+        assert!(allocate(&src, limit + 1).is_err());
+    }
+}
@@ -0,0 +1,139 @@
+use bytes::Bytes;
+use std::{borrow::Borrow, fmt, ops::{Deref, DerefMut}, str::Utf8Error};
+
+/// An immutable UTF-8 string backed by [`Bytes`].
+///
+/// Decoding a [`BytesStr`] slices it straight out of the frame instead of copying it into a
+/// new [`String`], and cloning it only bumps a reference count.
+#[derive(Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BytesStr(Bytes);
+
+impl BytesStr {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a [`BytesStr`] from a static string without copying.
+    pub const fn from_static(s: &'static str) -> Self {
+        Self(Bytes::from_static(s.as_bytes()))
+    }
+
+    /// Converts `bytes` into a [`BytesStr`] if it is valid UTF-8.
+    pub fn from_utf8(bytes: Bytes) -> Result<Self, Utf8Error> {
+        std::str::from_utf8(&bytes)?;
+        Ok(Self(bytes))
+    }
+
+    /// Returns the string slice.
+    pub fn as_str(&self) -> &str {
+        // SAFETY: the bytes are validated as UTF-8 on construction and never mutated
+        unsafe { std::str::from_utf8_unchecked(&self.0) }
+    }
+
+    /// Returns the underlying [`Bytes`].
+    pub fn as_bytes(&self) -> &Bytes {
+        &self.0
+    }
+
+    /// Consumes `self` and returns the underlying [`Bytes`].
+    pub fn into_bytes(self) -> Bytes {
+        self.0
+    }
+}
+
+impl Deref for BytesStr {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for BytesStr {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl Borrow<str> for BytesStr {
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl From<String> for BytesStr {
+    fn from(s: String) -> Self {
+        Self(Bytes::from(s))
+    }
+}
+
+impl From<&'static str> for BytesStr {
+    fn from(s: &'static str) -> Self {
+        Self::from_static(s)
+    }
+}
+
+impl From<BytesStr> for Bytes {
+    fn from(s: BytesStr) -> Self {
+        s.0
+    }
+}
+
+impl PartialEq<str> for BytesStr {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for BytesStr {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl fmt::Debug for BytesStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl fmt::Display for BytesStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.as_str(), f)
+    }
+}
+
+/// Wrapper that encodes a [`HashMap`](std::collections::HashMap) or [`HashSet`](std::collections::HashSet)
+/// in canonical order.
+///
+/// Entries are sorted by their encoded keys, so equal values always produce identical frames.
+/// The wire format is the same as for the unwrapped collection.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Canonical<T>(pub T);
+
+impl<T> Canonical<T> {
+    /// Consumes `self` and returns the wrapped value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for Canonical<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Canonical<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T> From<T> for Canonical<T> {
+    fn from(value: T) -> Self {
+        Self(value)
+    }
+}
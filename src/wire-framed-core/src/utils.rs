@@ -1,167 +1,995 @@
 use bytes::{Buf, Bytes, BufMut, BytesMut};
-use std::{io::{self, ErrorKind}, collections::HashSet, hash::Hash};
+use std::{collections::{btree_map, hash_map, BTreeMap, BTreeSet, HashMap, HashSet}, hash::{BuildHasher, Hash}, mem::{ManuallyDrop, MaybeUninit}, ptr};
+use std::{net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6}, time::{Duration, SystemTime, UNIX_EPOCH}};
 
-/// A utility function to get a [`bool`] from a [`Bytes`].
-pub fn get_bool(src: &mut Bytes, name: &str) -> Result<bool, std::io::Error> {
-	if src.len() < 1 { return Err(io::Error::new(ErrorKind::InvalidInput, format!("expected '{}'", name))) }
+use crate::{limits, BytesStr, DecodeError, DecodeErrorKind, EncodeError, LengthPrefix};
+
+const NANOS_PER_SEC: u32 = 1_000_000_000;
+const IP_V4: u8 = 0;
+const IP_V6: u8 = 1;
+/// The maximum number of bytes of a LEB128 encoded [`u64`].
+pub(crate) const MAX_VARINT_SIZE: usize = 10;
+
+/// Returns an [`DecodeErrorKind::UnexpectedEof`] error if `src` holds fewer than `needed` bytes.
+fn ensure(src: &impl Buf, name: &str, needed: usize) -> Result<(), DecodeError> {
+	if src.remaining() < needed {
+		let kind = DecodeErrorKind::UnexpectedEof { name: name.to_string(), needed, available: src.remaining() };
+		return Err(DecodeError::new(kind, src.remaining()));
+	}
+
+	Ok(())
+}
+
+/// Returns an [`DecodeErrorKind::InvalidTag`] error for a tag that was just read from `src`.
+fn invalid_tag(src: &impl Buf, name: &str, tag: u8) -> DecodeError {
+	DecodeError::new(DecodeErrorKind::InvalidTag { name: name.to_string(), tag: tag as u64 }, src.remaining() + 1)
+}
+
+/// Returns an [`DecodeErrorKind::InvalidValue`] error for a value of `len` bytes that was just read from `src`.
+fn invalid_value(src: &impl Buf, name: &str, len: usize, reason: String) -> DecodeError {
+	DecodeError::new(DecodeErrorKind::InvalidValue { name: name.to_string(), reason }, src.remaining() + len)
+}
+
+/// A utility function that fails if any bytes are left in a [`Buf`].
+pub fn check_trailing<B: Buf>(src: &B) -> Result<(), DecodeError> {
+	if src.has_remaining() {
+		return Err(DecodeError::new(DecodeErrorKind::TrailingBytes { count: src.remaining() }, src.remaining()));
+	}
+
+	Ok(())
+}
+
+/// A utility function to get a [`bool`] from a [`Buf`].
+pub fn get_bool<B: Buf>(src: &mut B, name: &str) -> Result<bool, DecodeError> {
+	ensure(src, name, 1)?;
 	Ok(src.get_u8() != 0)
 }
 
-/// A utility function to get a [`u8`] from a [`Bytes`].
-pub fn get_u8(src: &mut Bytes, name: &str) -> Result<u8, std::io::Error> {
-	if src.len() < 1 { return Err(io::Error::new(ErrorKind::InvalidInput, format!("expected '{}'", name))) }
+/// A utility function to get a [`u8`] from a [`Buf`].
+pub fn get_u8<B: Buf>(src: &mut B, name: &str) -> Result<u8, DecodeError> {
+	ensure(src, name, 1)?;
 	Ok(src.get_u8())
 }
 
-/// A utility function to get a [`u16`] from a [`Bytes`].
-pub fn get_u16(src: &mut Bytes, name: &str) -> Result<u16, std::io::Error> {
-	if src.len() < 2 { return Err(io::Error::new(ErrorKind::InvalidInput, format!("expected '{}'", name))) }
+/// A utility function to get a [`u16`] from a [`Buf`].
+pub fn get_u16<B: Buf>(src: &mut B, name: &str) -> Result<u16, DecodeError> {
+	ensure(src, name, 2)?;
 	Ok(src.get_u16())
 }
 
-/// A utility function to get a [`u32`] from a [`Bytes`].
-pub fn get_u32(src: &mut Bytes, name: &str) -> Result<u32, std::io::Error> {
-	if src.len() < 4 { return Err(io::Error::new(ErrorKind::InvalidInput, format!("expected '{}'", name))) }
+/// A utility function to get a [`u32`] from a [`Buf`].
+pub fn get_u32<B: Buf>(src: &mut B, name: &str) -> Result<u32, DecodeError> {
+	ensure(src, name, 4)?;
 	Ok(src.get_u32())
 }
 
-/// A utility function to get a [`u64`] from a [`Bytes`].
-pub fn get_u64(src: &mut Bytes, name: &str) -> Result<u64, std::io::Error> {
-	if src.len() < 8 { return Err(io::Error::new(ErrorKind::InvalidInput, format!("expected '{}'", name))) }
+/// A utility function to get a [`u64`] from a [`Buf`].
+pub fn get_u64<B: Buf>(src: &mut B, name: &str) -> Result<u64, DecodeError> {
+	ensure(src, name, 8)?;
 	Ok(src.get_u64())
 }
 
-/// A utility function to get a [`i8`] from a [`Bytes`].
-pub fn get_i8(src: &mut Bytes, name: &str) -> Result<i8, std::io::Error> {
-	if src.len() < 1 { return Err(io::Error::new(ErrorKind::InvalidInput, format!("expected '{}'", name))) }
+/// A utility function to get a [`i8`] from a [`Buf`].
+pub fn get_i8<B: Buf>(src: &mut B, name: &str) -> Result<i8, DecodeError> {
+	ensure(src, name, 1)?;
 	Ok(src.get_i8())
 }
 
-/// A utility function to get a [`i16`] from a [`Bytes`].
-pub fn get_i16(src: &mut Bytes, name: &str) -> Result<i16, std::io::Error> {
-	if src.len() < 2 { return Err(io::Error::new(ErrorKind::InvalidInput, format!("expected '{}'", name))) }
+/// A utility function to get a [`i16`] from a [`Buf`].
+pub fn get_i16<B: Buf>(src: &mut B, name: &str) -> Result<i16, DecodeError> {
+	ensure(src, name, 2)?;
 	Ok(src.get_i16())
 }
 
-/// A utility function to get a [`i32`] from a [`Bytes`].
-pub fn get_i32(src: &mut Bytes, name: &str) -> Result<i32, std::io::Error> {
-	if src.len() < 4 { return Err(io::Error::new(ErrorKind::InvalidInput, format!("expected '{}'", name))) }
+/// A utility function to get a [`i32`] from a [`Buf`].
+pub fn get_i32<B: Buf>(src: &mut B, name: &str) -> Result<i32, DecodeError> {
+	ensure(src, name, 4)?;
 	Ok(src.get_i32())
 }
 
-/// A utility function to get a [`i64`] from a [`Bytes`].
-pub fn get_i64(src: &mut Bytes, name: &str) -> Result<i64, std::io::Error> {
-	if src.len() < 8 { return Err(io::Error::new(ErrorKind::InvalidInput, format!("expected '{}'", name))) }
+/// A utility function to get a [`i64`] from a [`Buf`].
+pub fn get_i64<B: Buf>(src: &mut B, name: &str) -> Result<i64, DecodeError> {
+	ensure(src, name, 8)?;
 	Ok(src.get_i64())
 }
 
-/// A utility function to get a [`String`] from a [`Bytes`].
-pub fn get_string(src: &mut Bytes, name: &str) -> Result<String, std::io::Error> {
-	if src.len() < 4 { return Err(io::Error::new(ErrorKind::InvalidInput, format!("expected '{}' string size", name))) }
-	let len = src.get_u32() as usize;
-	if src.len() < len { return Err(io::Error::new(ErrorKind::InvalidInput, format!("expected '{}' string", name))) }
-	let s = src.copy_to_bytes(len);
-	let s = String::from_utf8(s.to_vec())
-		.map_err(|_| io::Error::new(ErrorKind::InvalidInput, format!("'{}' is not a valid UTF-8 string", name)))?;
+/// A utility function to get a [`u128`] from a [`Buf`].
+pub fn get_u128<B: Buf>(src: &mut B, name: &str) -> Result<u128, DecodeError> {
+	ensure(src, name, 16)?;
+	Ok(src.get_u128())
+}
+
+/// A utility function to get a [`i128`] from a [`Buf`].
+pub fn get_i128<B: Buf>(src: &mut B, name: &str) -> Result<i128, DecodeError> {
+	ensure(src, name, 16)?;
+	Ok(src.get_i128())
+}
+
+/// A utility function to get a [`usize`] from a [`Buf`].
+///
+/// `usize` is encoded as a [`u64`] on every target, so values that do not fit are rejected on 32-bit targets.
+pub fn get_usize<B: Buf>(src: &mut B, name: &str) -> Result<usize, DecodeError> {
+	let value = get_u64(src, name)?;
+	usize::try_from(value).map_err(|_| invalid_value(src, name, 8, format!("{} does not fit into a usize", value)))
+}
+
+/// A utility function to get a [`isize`] from a [`Buf`].
+///
+/// `isize` is encoded as a [`i64`] on every target, so values that do not fit are rejected on 32-bit targets.
+pub fn get_isize<B: Buf>(src: &mut B, name: &str) -> Result<isize, DecodeError> {
+	let value = get_i64(src, name)?;
+	isize::try_from(value).map_err(|_| invalid_value(src, name, 8, format!("{} does not fit into an isize", value)))
+}
+
+/// A utility function to get a [`f32`] from a [`Buf`].
+pub fn get_f32<B: Buf>(src: &mut B, name: &str) -> Result<f32, DecodeError> {
+	ensure(src, name, 4)?;
+	Ok(src.get_f32())
+}
+
+/// A utility function to get a [`f64`] from a [`Buf`].
+pub fn get_f64<B: Buf>(src: &mut B, name: &str) -> Result<f64, DecodeError> {
+	ensure(src, name, 8)?;
+	Ok(src.get_f64())
+}
+
+/// A utility function to get a [`char`] from a [`Buf`].
+pub fn get_char<B: Buf>(src: &mut B, name: &str) -> Result<char, DecodeError> {
+	let value = get_u32(src, name)?;
+	char::from_u32(value).ok_or_else(|| invalid_value(src, name, 4, format!("{:#x} is not a unicode scalar value", value)))
+}
+
+/// A utility function to get a non-zero integer, such as a [`NonZeroU32`](std::num::NonZeroU32), from a [`Buf`].
+pub fn get_non_zero<B: Buf, T, N: TryFrom<T>>(src: &mut B, name: &str, get: impl Fn(&mut B, &str) -> Result<T, DecodeError>) -> Result<N, DecodeError> {
+	let val = get(src, name)?;
+	N::try_from(val).map_err(|_| invalid_value(src, name, std::mem::size_of::<T>(), "value must not be zero".to_string()))
+}
+
+/// A utility function to get a [`u64`] written as an unsigned LEB128 varint from a [`Buf`].
+///
+/// Varints that overflow a [`u64`] and non-minimal encodings are rejected, so every value has a single encoding.
+pub fn get_varint_u64<B: Buf>(src: &mut B, name: &str) -> Result<u64, DecodeError> {
+	let mut value = 0u64;
+	for i in 0..MAX_VARINT_SIZE {
+		ensure(src, name, 1)?;
+		let byte = src.get_u8();
+		if i == MAX_VARINT_SIZE - 1 && byte > 1 {
+			return Err(invalid_value(src, name, i + 1, "varint overflows a u64".to_string()));
+		}
+
+		value |= ((byte & 0x7F) as u64) << (7 * i);
+		if byte & 0x80 == 0 {
+			if i > 0 && byte == 0 {
+				return Err(invalid_value(src, name, i + 1, "overlong varint".to_string()));
+			}
+
+			return Ok(value);
+		}
+	}
+
+	unreachable!("the last byte of a varint either ends it or overflows")
+}
+
+/// An integer that can be written as a LEB128 varint with [`put_varint`].
+///
+/// Signed integers are sign-extended first, so negative values always take ten bytes. Use [`ZigZag`] for
+/// signed values that are often small and negative.
+pub trait Varint: Copy {
+	/// Whether the written [`u64`] is a sign-extended [`i64`].
+	const SIGNED: bool;
+
+	/// Converts the value into the [`u64`] that is written.
+	fn to_varint(self) -> u64;
+	/// Converts a decoded [`u64`] back, returning `None` if it does not fit.
+	fn from_varint(value: u64) -> Option<Self>;
+}
+
+/// A signed integer that can be written as a zigzag encoded varint with [`put_zigzag`].
+///
+/// Zigzag encoding interleaves negative and positive values, so both take few bytes when they are close to zero.
+pub trait ZigZag: Copy {
+	/// Converts the value into the [`u64`] that is written.
+	fn to_zigzag(self) -> u64;
+	/// Converts a decoded [`u64`] back, returning `None` if it does not fit.
+	fn from_zigzag(value: u64) -> Option<Self>;
+}
+
+/// A number that can be written in little-endian byte order.
+///
+/// [`usize`] and [`isize`] are written as eight bytes, just like their big-endian encoding.
+pub trait LittleEndian: Sized {
+	/// Writes the value into a [`BytesMut`] in little-endian byte order.
+	fn put_le(&self, dst: &mut BytesMut);
+	/// Reads a value in little-endian byte order from a [`Buf`].
+	fn get_le<B: Buf>(src: &mut B, name: &str) -> Result<Self, DecodeError>;
+}
+
+macro_rules! varint_impls {
+	($($unsigned:ty),* ; $($signed:ty),*) => {
+		$(
+			impl Varint for $unsigned {
+				const SIGNED: bool = false;
+
+				fn to_varint(self) -> u64 {
+					self as u64
+				}
+
+				fn from_varint(value: u64) -> Option<Self> {
+					Self::try_from(value).ok()
+				}
+			}
+		)*
+
+		$(
+			impl Varint for $signed {
+				const SIGNED: bool = true;
+
+				fn to_varint(self) -> u64 {
+					self as i64 as u64
+				}
+
+				fn from_varint(value: u64) -> Option<Self> {
+					Self::try_from(value as i64).ok()
+				}
+			}
+
+			impl ZigZag for $signed {
+				fn to_zigzag(self) -> u64 {
+					let value = self as i64;
+					((value << 1) ^ (value >> 63)) as u64
+				}
+
+				fn from_zigzag(value: u64) -> Option<Self> {
+					Self::try_from((value >> 1) as i64 ^ -((value & 1) as i64)).ok()
+				}
+			}
+		)*
+	};
+}
+
+varint_impls!(u8, u16, u32, u64, usize; i8, i16, i32, i64, isize);
+
+macro_rules! little_endian_impls {
+	($($ty:ty => $put:ident, $get:ident, $size:literal;)*) => {
+		$(
+			impl LittleEndian for $ty {
+				fn put_le(&self, dst: &mut BytesMut) {
+					dst.$put(*self);
+				}
+
+				fn get_le<B: Buf>(src: &mut B, name: &str) -> Result<Self, DecodeError> {
+					ensure(src, name, $size)?;
+					Ok(src.$get())
+				}
+			}
+		)*
+	};
+}
+
+little_endian_impls! {
+	u8 => put_u8, get_u8, 1;
+	u16 => put_u16_le, get_u16_le, 2;
+	u32 => put_u32_le, get_u32_le, 4;
+	u64 => put_u64_le, get_u64_le, 8;
+	u128 => put_u128_le, get_u128_le, 16;
+	i8 => put_i8, get_i8, 1;
+	i16 => put_i16_le, get_i16_le, 2;
+	i32 => put_i32_le, get_i32_le, 4;
+	i64 => put_i64_le, get_i64_le, 8;
+	i128 => put_i128_le, get_i128_le, 16;
+	f32 => put_f32_le, get_f32_le, 4;
+	f64 => put_f64_le, get_f64_le, 8;
+}
+
+impl LittleEndian for usize {
+	fn put_le(&self, dst: &mut BytesMut) {
+		dst.put_u64_le(*self as u64);
+	}
+
+	fn get_le<B: Buf>(src: &mut B, name: &str) -> Result<Self, DecodeError> {
+		let value = u64::get_le(src, name)?;
+		usize::try_from(value).map_err(|_| invalid_value(src, name, 8, format!("{} does not fit into a usize", value)))
+	}
+}
+
+impl LittleEndian for isize {
+	fn put_le(&self, dst: &mut BytesMut) {
+		dst.put_i64_le(*self as i64);
+	}
+
+	fn get_le<B: Buf>(src: &mut B, name: &str) -> Result<Self, DecodeError> {
+		let value = i64::get_le(src, name)?;
+		isize::try_from(value).map_err(|_| invalid_value(src, name, 8, format!("{} does not fit into an isize", value)))
+	}
+}
+
+/// A utility function to get an integer written as a LEB128 varint from a [`Buf`].
+pub fn get_varint<B: Buf, T: Varint>(src: &mut B, name: &str) -> Result<T, DecodeError> {
+	let remaining = src.remaining();
+	let value = get_varint_u64(src, name)?;
+	T::from_varint(value).ok_or_else(|| {
+		let value = if T::SIGNED { (value as i64).to_string() } else { value.to_string() };
+		invalid_value(src, name, remaining - src.remaining(), format!("{} is out of range", value))
+	})
+}
+
+/// A utility function to get a signed integer written as a zigzag encoded varint from a [`Buf`].
+pub fn get_zigzag<B: Buf, T: ZigZag>(src: &mut B, name: &str) -> Result<T, DecodeError> {
+	let remaining = src.remaining();
+	let value = get_varint_u64(src, name)?;
+	T::from_zigzag(value).ok_or_else(|| invalid_value(src, name, remaining - src.remaining(), format!("{} is out of range", (value >> 1) as i64 ^ -((value & 1) as i64))))
+}
+
+/// A utility function to get a length prefix of the given width from a [`Buf`].
+///
+/// Length prefixes are always big-endian, and lengths that do not fit into a [`usize`] are rejected.
+pub fn get_length<B: Buf>(src: &mut B, name: &str, prefix: LengthPrefix) -> Result<usize, DecodeError> {
+	let remaining = src.remaining();
+	let len = match prefix {
+		LengthPrefix::U8 => get_u8(src, name)? as u64,
+		LengthPrefix::U16 => get_u16(src, name)? as u64,
+		LengthPrefix::U32 => get_u32(src, name)? as u64,
+		LengthPrefix::U64 => get_u64(src, name)?,
+		LengthPrefix::Varint => get_varint_u64(src, name)?,
+	};
+	usize::try_from(len).map_err(|_| invalid_value(src, name, remaining - src.remaining(), format!("length {} does not fit into a usize", len)))
+}
+
+/// A utility function to get a [`String`] from a [`Buf`].
+pub fn get_string<B: Buf>(src: &mut B, name: &str) -> Result<String, DecodeError> {
+	get_string_with_prefix(src, name, LengthPrefix::U32)
+}
+
+/// A utility function to get a [`String`] with a length prefix of the given width from a [`Buf`].
+pub fn get_string_with_prefix<B: Buf>(src: &mut B, name: &str, prefix: LengthPrefix) -> Result<String, DecodeError> {
+	let len = get_length(src, name, prefix)?;
+	ensure(src, name, len)?;
+	limits::check_string(src, name, len)?;
+
+	let mut s = vec![0; len];
+	src.copy_to_slice(&mut s);
+	String::from_utf8(s).map_err(|err| {
+		let kind = DecodeErrorKind::InvalidUtf8 { name: name.to_string(), source: err.utf8_error() };
+		DecodeError::new(kind, src.remaining() + len)
+	})
+}
+
+/// A utility function to get a [`Vec<u8>`] from a [`Buf`] in a single copy.
+pub fn get_byte_vec<B: Buf>(src: &mut B, name: &str) -> Result<Vec<u8>, DecodeError> {
+	get_byte_vec_with_prefix(src, name, LengthPrefix::U32)
+}
+
+/// A utility function to get a [`Vec<u8>`] with a length prefix of the given width from a [`Buf`] in a single copy.
+pub fn get_byte_vec_with_prefix<B: Buf>(src: &mut B, name: &str, prefix: LengthPrefix) -> Result<Vec<u8>, DecodeError> {
+	let len = get_length(src, name, prefix)?;
+	ensure(src, name, len)?;
+	limits::check_collection::<u8>(src, name, len)?;
+
+	let mut vec = vec![0; len];
+	src.copy_to_slice(&mut vec);
+	Ok(vec)
+}
+
+/// A utility function to get a [`Bytes`] from a [`Bytes`] without copying.
+pub fn get_bytes(src: &mut Bytes, name: &str) -> Result<Bytes, DecodeError> {
+	get_bytes_with_prefix(src, name, LengthPrefix::U32)
+}
+
+/// A utility function to get a [`Bytes`] with a length prefix of the given width from a [`Bytes`] without copying.
+pub fn get_bytes_with_prefix(src: &mut Bytes, name: &str, prefix: LengthPrefix) -> Result<Bytes, DecodeError> {
+	let len = get_length(src, name, prefix)?;
+	ensure(src, name, len)?;
+	Ok(src.split_to(len))
+}
+
+/// A utility function to get a [`BytesStr`] from a [`Bytes`] without copying.
+pub fn get_bytes_str(src: &mut Bytes, name: &str) -> Result<BytesStr, DecodeError> {
+	get_bytes_str_with_prefix(src, name, LengthPrefix::U32)
+}
+
+/// A utility function to get a [`BytesStr`] with a length prefix of the given width from a [`Bytes`] without copying.
+pub fn get_bytes_str_with_prefix(src: &mut Bytes, name: &str, prefix: LengthPrefix) -> Result<BytesStr, DecodeError> {
+	let bytes = get_bytes_with_prefix(src, name, prefix)?;
+	let remaining = src.remaining() + bytes.len();
+	BytesStr::from_utf8(bytes).map_err(|source| {
+		DecodeError::new(DecodeErrorKind::InvalidUtf8 { name: name.to_string(), source }, remaining)
+	})
+}
+
+/// A utility function to borrow a byte slice from a byte slice.
+pub fn get_slice<'a>(src: &mut &'a [u8], name: &str) -> Result<&'a [u8], DecodeError> {
+	get_slice_with_prefix(src, name, LengthPrefix::U32)
+}
+
+/// A utility function to borrow a byte slice with a length prefix of the given width from a byte slice.
+pub fn get_slice_with_prefix<'a>(src: &mut &'a [u8], name: &str, prefix: LengthPrefix) -> Result<&'a [u8], DecodeError> {
+	let len = get_length(src, name, prefix)?;
+	ensure(src, name, len)?;
+	let (slice, rest) = src.split_at(len);
+	*src = rest;
+	Ok(slice)
+}
+
+/// A utility function to borrow a [`&str`] from a byte slice.
+pub fn get_str<'a>(src: &mut &'a [u8], name: &str) -> Result<&'a str, DecodeError> {
+	get_str_with_prefix(src, name, LengthPrefix::U32)
+}
 
-	Ok(s)
+/// A utility function to borrow a [`&str`] with a length prefix of the given width from a byte slice.
+pub fn get_str_with_prefix<'a>(src: &mut &'a [u8], name: &str, prefix: LengthPrefix) -> Result<&'a str, DecodeError> {
+	let slice = get_slice_with_prefix(src, name, prefix)?;
+	std::str::from_utf8(slice).map_err(|source| {
+		DecodeError::new(DecodeErrorKind::InvalidUtf8 { name: name.to_string(), source }, src.remaining() + slice.len())
+	})
 }
 
-/// A utility function to get an [`Option`] from a [`Bytes`].
-pub fn get_option<T>(src: &mut Bytes, name: &str, get: impl Fn(&mut Bytes) -> Result<T, std::io::Error>) -> Result<Option<T>, std::io::Error> {
+/// A utility function to get a [`Duration`] from a [`Buf`] as seconds followed by nanoseconds.
+pub fn get_duration<B: Buf>(src: &mut B, name: &str) -> Result<Duration, DecodeError> {
+	let secs = get_u64(src, name)?;
+	let nanos = get_u32(src, name)?;
+	if nanos >= NANOS_PER_SEC {
+		return Err(invalid_value(src, name, 12, format!("{} nanoseconds is not less than one second", nanos)));
+	}
+
+	Ok(Duration::new(secs, nanos))
+}
+
+/// A utility function to get a [`SystemTime`] from a [`Buf`] as signed seconds since [`UNIX_EPOCH`] followed by nanoseconds.
+///
+/// The nanoseconds always count forward, so one nanosecond before the epoch is `-1` seconds and `999_999_999` nanoseconds.
+pub fn get_system_time<B: Buf>(src: &mut B, name: &str) -> Result<SystemTime, DecodeError> {
+	let secs = get_i64(src, name)?;
+	let nanos = get_u32(src, name)?;
+	if nanos >= NANOS_PER_SEC {
+		return Err(invalid_value(src, name, 12, format!("{} nanoseconds is not less than one second", nanos)));
+	}
+
+	let time = if secs >= 0 {
+		UNIX_EPOCH.checked_add(Duration::new(secs as u64, nanos))
+	} else {
+		UNIX_EPOCH.checked_sub(Duration::from_secs(secs.unsigned_abs())).and_then(|time| time.checked_add(Duration::from_nanos(nanos as u64)))
+	};
+
+	time.ok_or_else(|| invalid_value(src, name, 12, format!("{}s {}ns is out of range", secs, nanos)))
+}
+
+/// A utility function to get an [`Ipv4Addr`] from a [`Buf`].
+pub fn get_ipv4_addr<B: Buf>(src: &mut B, name: &str) -> Result<Ipv4Addr, DecodeError> {
+	get_byte_array::<_, 4>(src, name).map(Ipv4Addr::from)
+}
+
+/// A utility function to get an [`Ipv6Addr`] from a [`Buf`].
+pub fn get_ipv6_addr<B: Buf>(src: &mut B, name: &str) -> Result<Ipv6Addr, DecodeError> {
+	get_byte_array::<_, 16>(src, name).map(Ipv6Addr::from)
+}
+
+/// A utility function to get an [`IpAddr`] from a [`Buf`], tagged `0` for v4 and `1` for v6.
+pub fn get_ip_addr<B: Buf>(src: &mut B, name: &str) -> Result<IpAddr, DecodeError> {
+	let tag = get_u8(src, name)?;
+
+	match tag {
+		IP_V4 => get_ipv4_addr(src, name).map(IpAddr::V4),
+		IP_V6 => get_ipv6_addr(src, name).map(IpAddr::V6),
+		_ => Err(invalid_tag(src, name, tag)),
+	}
+}
+
+/// A utility function to get a [`SocketAddrV4`] from a [`Buf`].
+pub fn get_socket_addr_v4<B: Buf>(src: &mut B, name: &str) -> Result<SocketAddrV4, DecodeError> {
+	let ip = get_ipv4_addr(src, name)?;
+	let port = get_u16(src, name)?;
+	Ok(SocketAddrV4::new(ip, port))
+}
+
+/// A utility function to get a [`SocketAddrV6`] from a [`Buf`].
+pub fn get_socket_addr_v6<B: Buf>(src: &mut B, name: &str) -> Result<SocketAddrV6, DecodeError> {
+	let ip = get_ipv6_addr(src, name)?;
+	let port = get_u16(src, name)?;
+	let flowinfo = get_u32(src, name)?;
+	let scope_id = get_u32(src, name)?;
+	Ok(SocketAddrV6::new(ip, port, flowinfo, scope_id))
+}
+
+/// A utility function to get a [`SocketAddr`] from a [`Buf`], tagged `0` for v4 and `1` for v6.
+pub fn get_socket_addr<B: Buf>(src: &mut B, name: &str) -> Result<SocketAddr, DecodeError> {
+	let tag = get_u8(src, name)?;
+
+	match tag {
+		IP_V4 => get_socket_addr_v4(src, name).map(SocketAddr::V4),
+		IP_V6 => get_socket_addr_v6(src, name).map(SocketAddr::V6),
+		_ => Err(invalid_tag(src, name, tag)),
+	}
+}
+
+/// A utility function to get an [`Option`] from a [`Buf`].
+pub fn get_option<B: Buf, T, E: Into<DecodeError>>(src: &mut B, name: &str, get: impl Fn(&mut B) -> Result<T, E>) -> Result<Option<T>, DecodeError> {
 	const NO_VALUE: u8 = 0;
 	const HAS_VALUE: u8 = 1;
 
-	if src.len() < 1 { return Err(io::Error::new(ErrorKind::InvalidInput, format!("expected '{}' option tag", name))) }
-	let tag = src.get_u8();
+	let tag = get_u8(src, name)?;
 
 	match tag {
 		NO_VALUE => Ok(None),
 		HAS_VALUE => {
-			let val = get(src)?;
+			let val = get(src).map_err(Into::into)?;
 			Ok(Some(val))
 		},
-		_ => Err(io::Error::new(ErrorKind::InvalidInput, "invalid option tag")),
+		_ => Err(invalid_tag(src, name, tag)),
 	}
 }
 
-/// A utility function to get an [`Vec<Option>`] from a [`Bytes`].
-pub fn get_option_array<T>(src: &mut Bytes, name: &str, get: impl Fn(&mut Bytes) -> Result<T, std::io::Error>) -> Result<Vec<Option<T>>, std::io::Error> {
+/// A utility function to get a [`Result`] from a [`Buf`].
+pub fn get_result<B, T, E, ET, EE>(src: &mut B, name: &str, get_ok: impl Fn(&mut B) -> Result<T, ET>, get_err: impl Fn(&mut B) -> Result<E, EE>) -> Result<Result<T, E>, DecodeError>
+where
+	B: Buf,
+	ET: Into<DecodeError>,
+	EE: Into<DecodeError>,
+{
+	const OK: u8 = 0;
+	const ERR: u8 = 1;
+
+	let tag = get_u8(src, name)?;
+
+	match tag {
+		OK => {
+			let val = get_ok(src).map_err(|err| err.into().in_variant("Ok"))?;
+			Ok(Ok(val))
+		},
+		ERR => {
+			let val = get_err(src).map_err(|err| err.into().in_variant("Err"))?;
+			Ok(Err(val))
+		},
+		_ => Err(invalid_tag(src, name, tag)),
+	}
+}
+
+/// A utility function to get an [`Vec<Option>`] from a [`Buf`].
+pub fn get_option_array<B: Buf, T, E: Into<DecodeError>>(src: &mut B, name: &str, get: impl Fn(&mut B) -> Result<T, E>) -> Result<Vec<Option<T>>, DecodeError> {
+	get_option_array_with_prefix(src, name, LengthPrefix::U32, get)
+}
+
+/// A utility function to get an [`Vec<Option>`] with a length prefix of the given width from a [`Buf`].
+pub fn get_option_array_with_prefix<B: Buf, T, E: Into<DecodeError>>(src: &mut B, name: &str, prefix: LengthPrefix, get: impl Fn(&mut B) -> Result<T, E>) -> Result<Vec<Option<T>>, DecodeError> {
 	const NO_VALUE: u8 = 0;
 	const HAS_VALUE: u8 = 1;
 
-	if src.len() < 4 { return Err(io::Error::new(ErrorKind::InvalidInput, format!("expected '{}' option array size", name))) }
-	let len = src.get_u32() as usize;
+	let len = get_length(src, name, prefix)?;
+	limits::check_collection::<Option<T>>(src, name, len)?;
+	let _guard = limits::enter(src)?;
 
 	let mut arr = Vec::default();
 	for i in 0..len {
-		if src.len() < 1 { return Err(io::Error::new(ErrorKind::InvalidInput, format!("expected '{}' at {}. option tag", name, i))) }
-		let tag = src.get_u8();
+		let tag = get_u8(src, name).map_err(|err| err.in_index(i))?;
 	
 		match tag {
 			NO_VALUE => arr.push(None),
 			HAS_VALUE => {
-				let val = get(src)?;
+				let val = get(src).map_err(|err| err.into().in_index(i))?;
 				arr.push(Some(val));
 			},
-			_ => return Err(io::Error::new(ErrorKind::InvalidInput, format!("invalid option tag at {}", i))),
+			_ => return Err(invalid_tag(src, name, tag).in_index(i)),
 		}
 	}
 
 	Ok(arr)
 }
 
-/// A utility function to get a [`Vec`] from a [`Bytes`].
-pub fn get_array<T>(src: &mut Bytes, name: &str, get: impl Fn(&mut Bytes) -> Result<T, std::io::Error>) -> Result<Vec<T>, std::io::Error> {
-	if src.len() < 4 { return Err(io::Error::new(ErrorKind::InvalidInput, format!("expected '{}' array size", name))) }
-	let len = src.get_u32() as usize;
+/// A utility function to get a [`Vec`] from a [`Buf`].
+pub fn get_array<B: Buf, T, E: Into<DecodeError>>(src: &mut B, name: &str, get: impl Fn(&mut B) -> Result<T, E>) -> Result<Vec<T>, DecodeError> {
+	get_array_with_prefix(src, name, LengthPrefix::U32, get)
+}
+
+/// A utility function to get a [`Vec`] with a length prefix of the given width from a [`Buf`].
+pub fn get_array_with_prefix<B: Buf, T, E: Into<DecodeError>>(src: &mut B, name: &str, prefix: LengthPrefix, get: impl Fn(&mut B) -> Result<T, E>) -> Result<Vec<T>, DecodeError> {
+	let len = get_length(src, name, prefix)?;
+	limits::check_collection::<T>(src, name, len)?;
+	let _guard = limits::enter(src)?;
 
 	let mut arr = Vec::default();
-	for _ in 0..len {
-		let val = get(src)?;
+	for i in 0..len {
+		let val = get(src).map_err(|err| err.into().in_index(i))?;
 		arr.push(val);
 	}
 
 	Ok(arr)
 }
 
-/// A utility function to get a [`HashSet`] from a [`Bytes`].
-pub fn get_hashset<T: PartialEq + Eq + Hash>(src: &mut Bytes, name: &str, get: impl Fn(&mut Bytes) -> Result<T, std::io::Error>) -> Result<HashSet<T>, std::io::Error> {
-	if src.len() < 4 { return Err(io::Error::new(ErrorKind::InvalidInput, format!("expected '{}' hashset size", name))) }
-	let len = src.get_u32() as usize;
+/// Elements of a fixed-size array decoded so far, dropped again if decoding fails.
+struct PartialArray<T, const N: usize> {
+	items: [MaybeUninit<T>; N],
+	len: usize,
+}
+
+impl<T, const N: usize> Drop for PartialArray<T, N> {
+	fn drop(&mut self) {
+		for item in &mut self.items[..self.len] {
+			// SAFETY: the first `len` items are initialized
+			unsafe { item.assume_init_drop() };
+		}
+	}
+}
 
-	let mut hashset = HashSet::default();
-	for _ in 0..len {
-		let val = get(src)?;
-		hashset.insert(val);
+/// A utility function to get a fixed-size array from a [`Buf`] without a length prefix.
+///
+/// If an element fails to decode, the elements decoded before it are dropped.
+pub fn get_fixed_array<B: Buf, T, E: Into<DecodeError>, const N: usize>(src: &mut B, get: impl Fn(&mut B) -> Result<T, E>) -> Result<[T; N], DecodeError> {
+	let _guard = limits::enter(src)?;
+	let mut arr = PartialArray::<T, N> { items: [const { MaybeUninit::uninit() }; N], len: 0 };
+	while arr.len < N {
+		let val = get(src).map_err(|err| err.into().in_index(arr.len))?;
+		arr.items[arr.len].write(val);
+		arr.len += 1;
 	}
 
-	Ok(hashset)
+	let arr = ManuallyDrop::new(arr);
+	// SAFETY: all `N` items are initialized and `arr` is never dropped, so they are moved out exactly once
+	Ok(unsafe { ptr::read(arr.items.as_ptr().cast::<[T; N]>()) })
+}
+
+/// A utility function to get a fixed-size byte array from a [`Buf`] in a single copy.
+pub fn get_byte_array<B: Buf, const N: usize>(src: &mut B, name: &str) -> Result<[u8; N], DecodeError> {
+	ensure(src, name, N)?;
+	let mut arr = [0; N];
+	src.copy_to_slice(&mut arr);
+	Ok(arr)
+}
+
+/// A set that can be decoded with [`get_set`].
+pub trait SetCollection<T>: Default {
+	/// Inserts `value`, returning `false` if it was already present.
+	fn insert_unique(&mut self, value: T) -> bool;
+}
+
+impl<T: Eq + Hash, S: BuildHasher + Default> SetCollection<T> for HashSet<T, S> {
+	fn insert_unique(&mut self, value: T) -> bool {
+		self.insert(value)
+	}
+}
+
+impl<T: Ord> SetCollection<T> for BTreeSet<T> {
+	fn insert_unique(&mut self, value: T) -> bool {
+		self.insert(value)
+	}
+}
+
+/// A map that can be decoded with [`get_map`].
+pub trait MapCollection<K, V>: Default {
+	/// Inserts `key` and `value`, returning `false` if `key` was already present.
+	fn insert_unique(&mut self, key: K, value: V) -> bool;
+}
+
+impl<K: Eq + Hash, V, S: BuildHasher + Default> MapCollection<K, V> for HashMap<K, V, S> {
+	fn insert_unique(&mut self, key: K, value: V) -> bool {
+		match self.entry(key) {
+			hash_map::Entry::Occupied(_) => false,
+			hash_map::Entry::Vacant(entry) => {
+				entry.insert(value);
+				true
+			},
+		}
+	}
+}
+
+impl<K: Ord, V> MapCollection<K, V> for BTreeMap<K, V> {
+	fn insert_unique(&mut self, key: K, value: V) -> bool {
+		match self.entry(key) {
+			btree_map::Entry::Occupied(_) => false,
+			btree_map::Entry::Vacant(entry) => {
+				entry.insert(value);
+				true
+			},
+		}
+	}
+}
+
+/// A utility function to get a [`HashSet`] from a [`Buf`].
+///
+/// Fails with [`DecodeErrorKind::DuplicateKey`] if an element appears more than once.
+pub fn get_hashset<B: Buf, T: PartialEq + Eq + Hash, E: Into<DecodeError>>(src: &mut B, name: &str, get: impl Fn(&mut B) -> Result<T, E>) -> Result<HashSet<T>, DecodeError> {
+	get_set(src, name, get)
+}
+
+/// A utility function to get a set, such as a [`HashSet`] or a [`BTreeSet`], from a [`Buf`].
+///
+/// Fails with [`DecodeErrorKind::DuplicateKey`] if an element appears more than once.
+pub fn get_set<B: Buf, S: SetCollection<T>, T, E: Into<DecodeError>>(src: &mut B, name: &str, get: impl Fn(&mut B) -> Result<T, E>) -> Result<S, DecodeError> {
+	get_set_with_prefix(src, name, LengthPrefix::U32, get)
+}
+
+/// A utility function to get a set with a length prefix of the given width from a [`Buf`].
+///
+/// Fails with [`DecodeErrorKind::DuplicateKey`] if an element appears more than once.
+pub fn get_set_with_prefix<B: Buf, S: SetCollection<T>, T, E: Into<DecodeError>>(src: &mut B, name: &str, prefix: LengthPrefix, get: impl Fn(&mut B) -> Result<T, E>) -> Result<S, DecodeError> {
+	let len = get_length(src, name, prefix)?;
+	limits::check_collection::<T>(src, name, len)?;
+	let _guard = limits::enter(src)?;
+
+	let mut set = S::default();
+	for i in 0..len {
+		let remaining = src.remaining();
+		let val = get(src).map_err(|err| err.into().in_index(i))?;
+		if !set.insert_unique(val) {
+			return Err(DecodeError::new(DecodeErrorKind::DuplicateKey { name: name.to_string() }, remaining).in_index(i));
+		}
+	}
+
+	Ok(set)
+}
+
+/// A utility function to get a map, such as a [`HashMap`] or a [`BTreeMap`], from a [`Buf`].
+///
+/// Fails with [`DecodeErrorKind::DuplicateKey`] if a key appears more than once.
+pub fn get_map<B, M, K, V, EK, EV>(src: &mut B, name: &str, get_key: impl Fn(&mut B) -> Result<K, EK>, get_value: impl Fn(&mut B) -> Result<V, EV>) -> Result<M, DecodeError>
+where
+	B: Buf,
+	M: MapCollection<K, V>,
+	EK: Into<DecodeError>,
+	EV: Into<DecodeError>,
+{
+	get_map_with_prefix(src, name, LengthPrefix::U32, get_key, get_value)
+}
+
+/// A utility function to get a map with a length prefix of the given width from a [`Buf`].
+///
+/// Fails with [`DecodeErrorKind::DuplicateKey`] if a key appears more than once.
+pub fn get_map_with_prefix<B, M, K, V, EK, EV>(src: &mut B, name: &str, prefix: LengthPrefix, get_key: impl Fn(&mut B) -> Result<K, EK>, get_value: impl Fn(&mut B) -> Result<V, EV>) -> Result<M, DecodeError>
+where
+	B: Buf,
+	M: MapCollection<K, V>,
+	EK: Into<DecodeError>,
+	EV: Into<DecodeError>,
+{
+	let len = get_length(src, name, prefix)?;
+	limits::check_collection::<(K, V)>(src, name, len)?;
+	let _guard = limits::enter(src)?;
+
+	let mut map = M::default();
+	for i in 0..len {
+		let remaining = src.remaining();
+		let key = get_key(src).map_err(|err| err.into().in_index(i))?;
+		let value = get_value(src).map_err(|err| err.into().in_index(i))?;
+		if !map.insert_unique(key, value) {
+			return Err(DecodeError::new(DecodeErrorKind::DuplicateKey { name: name.to_string() }, remaining).in_index(i));
+		}
+	}
+
+	Ok(map)
+}
+
+/// Panics with the error of a fallible `try_` function.
+///
+/// Used by [`IntoFrame::extend_frame`](crate::IntoFrame::extend_frame) implementations that forward to
+/// [`IntoFrame::try_extend_frame`](crate::IntoFrame::try_extend_frame), since there is nobody to return the
+/// error to and writing a truncated length would corrupt the frame.
+pub fn expect_encoded(result: Result<(), EncodeError>) {
+	if let Err(err) = result {
+		panic!("{}", err);
+	}
+}
+
+/// A utility function to put a length prefix of the given width into a [`BytesMut`].
+///
+/// Length prefixes are always big-endian.
+///
+/// # Panics
+/// Panics if `len` does not fit into the prefix, [`try_put_length`] returns an error instead.
+pub fn put_length(dst: &mut BytesMut, len: usize, prefix: LengthPrefix) {
+	expect_encoded(try_put_length(dst, len, prefix));
+}
+
+/// A utility function to put a length prefix of the given width into a [`BytesMut`], failing if `len`
+/// does not fit into the prefix instead of truncating it.
+pub fn try_put_length(dst: &mut BytesMut, len: usize, prefix: LengthPrefix) -> Result<(), EncodeError> {
+	if len as u64 > prefix.max_value() {
+		return Err(EncodeError::LengthOverflow { length: len, prefix });
+	}
+
+	match prefix {
+		LengthPrefix::U8 => dst.put_u8(len as u8),
+		LengthPrefix::U16 => dst.put_u16(len as u16),
+		LengthPrefix::U32 => dst.put_u32(len as u32),
+		LengthPrefix::U64 => dst.put_u64(len as u64),
+		LengthPrefix::Varint => put_varint_u64(dst, len as u64),
+	}
+	Ok(())
+}
+
+/// Returns the number of bytes [`put_length`] writes for `len`.
+pub fn length_prefix_len(len: usize, prefix: LengthPrefix) -> usize {
+	match prefix {
+		LengthPrefix::Varint => varint_len_u64(len as u64),
+		prefix => prefix.size(),
+	}
+}
+
+/// A string or collection that can be written with any [`LengthPrefix`], as done by `#[wire(len = ...)]`.
+pub trait LengthPrefixed {
+	/// Writes the value behind a length prefix of the given width, failing if a length does not fit into its prefix.
+	fn try_put_with_prefix(&self, dst: &mut BytesMut, prefix: LengthPrefix) -> Result<(), EncodeError>;
+
+	/// Returns the number of bytes [`LengthPrefixed::try_put_with_prefix`] writes.
+	fn encoded_len_with_prefix(&self, prefix: LengthPrefix) -> usize;
+}
+
+/// A string or collection that can be read with any [`LengthPrefix`] from a frame of type `F`,
+/// which is [`Bytes`] or a borrowed byte slice.
+pub trait FromLengthPrefixed<F>: Sized {
+	/// Reads the value behind a length prefix of the given width.
+	fn get_with_prefix(src: &mut F, name: &str, prefix: LengthPrefix) -> Result<Self, DecodeError>;
 }
 
 /// A utility function to put a [`&str`] into a [`BytesMut`].
 pub fn put_str(dst: &mut BytesMut, s: &str) {
-	dst.put_u32(s.len() as u32);
+	put_str_with_prefix(dst, s, LengthPrefix::U32);
+}
+
+/// A utility function to put a [`&str`] with a length prefix of the given width into a [`BytesMut`].
+pub fn put_str_with_prefix(dst: &mut BytesMut, s: &str, prefix: LengthPrefix) {
+	expect_encoded(try_put_str_with_prefix(dst, s, prefix));
+}
+
+/// A utility function to put a [`&str`] with a length prefix of the given width into a [`BytesMut`],
+/// failing if its length does not fit into the prefix.
+pub fn try_put_str_with_prefix(dst: &mut BytesMut, s: &str, prefix: LengthPrefix) -> Result<(), EncodeError> {
+	try_put_length(dst, s.len(), prefix)?;
 	dst.put_slice(s.as_bytes());
+	Ok(())
+}
+
+/// A utility function to put a [`Duration`] into a [`BytesMut`].
+pub fn put_duration(dst: &mut BytesMut, duration: &Duration) {
+	dst.put_u64(duration.as_secs());
+	dst.put_u32(duration.subsec_nanos());
+}
+
+/// A utility function to put a [`SystemTime`] into a [`BytesMut`].
+///
+/// # Panics
+/// Panics if the seconds do not fit into an [`i64`], [`try_put_system_time`] returns an error instead.
+/// Unix times always fit, so this only happens on platforms where [`SystemTime`] has a wider range.
+pub fn put_system_time(dst: &mut BytesMut, time: &SystemTime) {
+	expect_encoded(try_put_system_time(dst, time));
+}
+
+/// A utility function to put a [`SystemTime`] into a [`BytesMut`] as signed seconds since [`UNIX_EPOCH`]
+/// followed by nanoseconds, failing if the seconds do not fit into an [`i64`].
+pub fn try_put_system_time(dst: &mut BytesMut, time: &SystemTime) -> Result<(), EncodeError> {
+	let (secs, nanos) = match time.duration_since(UNIX_EPOCH) {
+		Ok(after) => (after.as_secs() as i128, after.subsec_nanos()),
+		Err(err) => {
+			let before = err.duration();
+			match before.subsec_nanos() {
+				0 => (-(before.as_secs() as i128), 0),
+				nanos => (-(before.as_secs() as i128) - 1, NANOS_PER_SEC - nanos),
+			}
+		},
+	};
+
+	let secs = i64::try_from(secs).map_err(|_| {
+		EncodeError::InvalidValue { name: "SystemTime".to_string(), reason: format!("{}s {}ns is out of range", secs, nanos) }
+	})?;
+	dst.put_i64(secs);
+	dst.put_u32(nanos);
+	Ok(())
+}
+
+/// A utility function to put an [`Ipv4Addr`] into a [`BytesMut`].
+pub fn put_ipv4_addr(dst: &mut BytesMut, ip: &Ipv4Addr) {
+	dst.put_slice(&ip.octets());
+}
+
+/// A utility function to put an [`Ipv6Addr`] into a [`BytesMut`].
+pub fn put_ipv6_addr(dst: &mut BytesMut, ip: &Ipv6Addr) {
+	dst.put_slice(&ip.octets());
+}
+
+/// A utility function to put an [`IpAddr`] into a [`BytesMut`].
+pub fn put_ip_addr(dst: &mut BytesMut, ip: &IpAddr) {
+	match ip {
+		IpAddr::V4(ip) => {
+			dst.put_u8(IP_V4);
+			put_ipv4_addr(dst, ip);
+		},
+		IpAddr::V6(ip) => {
+			dst.put_u8(IP_V6);
+			put_ipv6_addr(dst, ip);
+		},
+	}
+}
+
+/// A utility function to put a [`SocketAddrV4`] into a [`BytesMut`].
+pub fn put_socket_addr_v4(dst: &mut BytesMut, addr: &SocketAddrV4) {
+	put_ipv4_addr(dst, addr.ip());
+	dst.put_u16(addr.port());
+}
+
+/// A utility function to put a [`SocketAddrV6`] into a [`BytesMut`].
+pub fn put_socket_addr_v6(dst: &mut BytesMut, addr: &SocketAddrV6) {
+	put_ipv6_addr(dst, addr.ip());
+	dst.put_u16(addr.port());
+	dst.put_u32(addr.flowinfo());
+	dst.put_u32(addr.scope_id());
+}
+
+/// A utility function to put a [`SocketAddr`] into a [`BytesMut`].
+pub fn put_socket_addr(dst: &mut BytesMut, addr: &SocketAddr) {
+	match addr {
+		SocketAddr::V4(addr) => {
+			dst.put_u8(IP_V4);
+			put_socket_addr_v4(dst, addr);
+		},
+		SocketAddr::V6(addr) => {
+			dst.put_u8(IP_V6);
+			put_socket_addr_v6(dst, addr);
+		},
+	}
 }
 
 /// A utility function to put an [`Option`] into a [`BytesMut`].
 pub fn put_option<T>(dst: &mut BytesMut, opt: &Option<T>, put: impl Fn(&mut BytesMut, &T)) {
+	expect_encoded(try_put_option(dst, opt, |dst, val| {
+		put(dst, val);
+		Ok(())
+	}));
+}
+
+/// A utility function to put an [`Option`] into a [`BytesMut`], failing if `put` fails.
+pub fn try_put_option<T>(dst: &mut BytesMut, opt: &Option<T>, put: impl Fn(&mut BytesMut, &T) -> Result<(), EncodeError>) -> Result<(), EncodeError> {
 	const NO_VALUE: u8 = 0;
 	const HAS_VALUE: u8 = 1;
 
 	match opt {
 		Some(val) => {
 			dst.put_u8(HAS_VALUE);
-			put(dst, &val);
+			put(dst, val)
+		},
+		None => {
+			dst.put_u8(NO_VALUE);
+			Ok(())
+		},
+	}
+}
+
+/// A utility function to put a [`Result`] into a [`BytesMut`].
+pub fn put_result<T, E>(dst: &mut BytesMut, res: &Result<T, E>, put_ok: impl Fn(&mut BytesMut, &T), put_err: impl Fn(&mut BytesMut, &E)) {
+	expect_encoded(try_put_result(dst, res, |dst, val| {
+		put_ok(dst, val);
+		Ok(())
+	}, |dst, val| {
+		put_err(dst, val);
+		Ok(())
+	}));
+}
+
+/// A utility function to put a [`Result`] into a [`BytesMut`], failing if `put_ok` or `put_err` fails.
+pub fn try_put_result<T, E>(dst: &mut BytesMut, res: &Result<T, E>, put_ok: impl Fn(&mut BytesMut, &T) -> Result<(), EncodeError>, put_err: impl Fn(&mut BytesMut, &E) -> Result<(), EncodeError>) -> Result<(), EncodeError> {
+	const OK: u8 = 0;
+	const ERR: u8 = 1;
+
+	match res {
+		Ok(val) => {
+			dst.put_u8(OK);
+			put_ok(dst, val)
+		},
+		Err(val) => {
+			dst.put_u8(ERR);
+			put_err(dst, val)
 		},
-		None => dst.put_u8(NO_VALUE),
 	}
 }
 
 /// A utility function to put a [`Vec`] into a [`BytesMut`].
 pub fn put_array<T>(dst: &mut BytesMut, arr: &[T], put: impl Fn(&mut BytesMut, &T)) {
-	dst.put_u32(arr.len() as u32);
+	put_array_with_prefix(dst, arr, LengthPrefix::U32, put);
+}
+
+/// A utility function to put a [`Vec`] with a length prefix of the given width into a [`BytesMut`].
+pub fn put_array_with_prefix<T>(dst: &mut BytesMut, arr: &[T], prefix: LengthPrefix, put: impl Fn(&mut BytesMut, &T)) {
+	put_iter_with_prefix(dst, arr.iter(), prefix, put);
+}
+
+/// A utility function to put a fixed-size array into a [`BytesMut`] without a length prefix.
+pub fn put_fixed_array<T>(dst: &mut BytesMut, arr: &[T], put: impl Fn(&mut BytesMut, &T)) {
 	for val in arr {
 		put(dst, val);
 	}
@@ -169,18 +997,197 @@ pub fn put_array<T>(dst: &mut BytesMut, arr: &[T], put: impl Fn(&mut BytesMut, &
 
 /// A utility function to put a [`HashSet`] into a [`BytesMut`].
 pub fn put_hashset<T: PartialEq + Eq + Hash>(dst: &mut BytesMut, hashset: &HashSet<T>, put: impl Fn(&mut BytesMut, &T)) {
-	dst.put_u32(hashset.len() as u32);
-	for val in hashset {
+	put_hashset_with_prefix(dst, hashset, LengthPrefix::U32, put);
+}
+
+/// A utility function to put a [`HashSet`] with a length prefix of the given width into a [`BytesMut`].
+pub fn put_hashset_with_prefix<T: PartialEq + Eq + Hash>(dst: &mut BytesMut, hashset: &HashSet<T>, prefix: LengthPrefix, put: impl Fn(&mut BytesMut, &T)) {
+	put_iter_with_prefix(dst, hashset.iter(), prefix, put);
+}
+
+/// A utility function to put the elements of any sized collection into a [`BytesMut`].
+pub fn put_iter<'a, T: 'a>(dst: &mut BytesMut, iter: impl ExactSizeIterator<Item = &'a T>, put: impl Fn(&mut BytesMut, &T)) {
+	put_iter_with_prefix(dst, iter, LengthPrefix::U32, put);
+}
+
+/// A utility function to put the elements of any sized collection with a length prefix of the given width into a [`BytesMut`].
+pub fn put_iter_with_prefix<'a, T: 'a>(dst: &mut BytesMut, iter: impl ExactSizeIterator<Item = &'a T>, prefix: LengthPrefix, put: impl Fn(&mut BytesMut, &T)) {
+	expect_encoded(try_put_iter_with_prefix(dst, iter, prefix, |dst, val| {
+		put(dst, val);
+		Ok(())
+	}));
+}
+
+/// A utility function to put the elements of any sized collection with a length prefix of the given width into a [`BytesMut`],
+/// failing if the length does not fit into the prefix or `put` fails.
+pub fn try_put_iter_with_prefix<'a, T: 'a>(dst: &mut BytesMut, iter: impl ExactSizeIterator<Item = &'a T>, prefix: LengthPrefix, put: impl Fn(&mut BytesMut, &T) -> Result<(), EncodeError>) -> Result<(), EncodeError> {
+	try_put_length(dst, iter.len(), prefix)?;
+	for val in iter {
+		put(dst, val)?;
+	}
+	Ok(())
+}
+
+/// A utility function to put the elements of a set into a [`BytesMut`] in canonical order.
+///
+/// The elements are sorted by their encoded bytes, so equal sets always produce equal frames
+/// regardless of their iteration order.
+pub fn put_set_canonical<'a, T: 'a>(dst: &mut BytesMut, iter: impl ExactSizeIterator<Item = &'a T>, put: impl Fn(&mut BytesMut, &T)) {
+	put_set_canonical_with_prefix(dst, iter, LengthPrefix::U32, put);
+}
+
+/// A utility function to put the elements of a set with a length prefix of the given width into a [`BytesMut`] in canonical order.
+pub fn put_set_canonical_with_prefix<'a, T: 'a>(dst: &mut BytesMut, iter: impl ExactSizeIterator<Item = &'a T>, prefix: LengthPrefix, put: impl Fn(&mut BytesMut, &T)) {
+	expect_encoded(try_put_set_canonical_with_prefix(dst, iter, prefix, |dst, val| {
 		put(dst, val);
+		Ok(())
+	}));
+}
+
+/// A utility function to put the elements of a set with a length prefix of the given width into a [`BytesMut`]
+/// in canonical order, failing if the length does not fit into the prefix or `put` fails.
+pub fn try_put_set_canonical_with_prefix<'a, T: 'a>(dst: &mut BytesMut, iter: impl ExactSizeIterator<Item = &'a T>, prefix: LengthPrefix, put: impl Fn(&mut BytesMut, &T) -> Result<(), EncodeError>) -> Result<(), EncodeError> {
+	let mut encoded = iter.map(|val| {
+		let mut buf = BytesMut::new();
+		put(&mut buf, val)?;
+		Ok(buf)
+	}).collect::<Result<Vec<_>, EncodeError>>()?;
+	encoded.sort_unstable();
+
+	try_put_length(dst, encoded.len(), prefix)?;
+	for buf in encoded {
+		dst.put_slice(&buf);
 	}
+	Ok(())
+}
+
+/// A utility function to put a map into a [`BytesMut`].
+pub fn put_map<'a, K: 'a, V: 'a>(dst: &mut BytesMut, iter: impl ExactSizeIterator<Item = (&'a K, &'a V)>, put_key: impl Fn(&mut BytesMut, &K), put_value: impl Fn(&mut BytesMut, &V)) {
+	put_map_with_prefix(dst, iter, LengthPrefix::U32, put_key, put_value);
+}
+
+/// A utility function to put a map with a length prefix of the given width into a [`BytesMut`].
+pub fn put_map_with_prefix<'a, K: 'a, V: 'a>(dst: &mut BytesMut, iter: impl ExactSizeIterator<Item = (&'a K, &'a V)>, prefix: LengthPrefix, put_key: impl Fn(&mut BytesMut, &K), put_value: impl Fn(&mut BytesMut, &V)) {
+	expect_encoded(try_put_map_with_prefix(dst, iter, prefix, |dst, key| {
+		put_key(dst, key);
+		Ok(())
+	}, |dst, value| {
+		put_value(dst, value);
+		Ok(())
+	}));
+}
+
+/// A utility function to put a map with a length prefix of the given width into a [`BytesMut`],
+/// failing if the length does not fit into the prefix or `put_key` or `put_value` fails.
+pub fn try_put_map_with_prefix<'a, K: 'a, V: 'a>(dst: &mut BytesMut, iter: impl ExactSizeIterator<Item = (&'a K, &'a V)>, prefix: LengthPrefix, put_key: impl Fn(&mut BytesMut, &K) -> Result<(), EncodeError>, put_value: impl Fn(&mut BytesMut, &V) -> Result<(), EncodeError>) -> Result<(), EncodeError> {
+	try_put_length(dst, iter.len(), prefix)?;
+	for (key, value) in iter {
+		put_key(dst, key)?;
+		put_value(dst, value)?;
+	}
+	Ok(())
+}
+
+/// A utility function to put a map into a [`BytesMut`] in canonical order.
+///
+/// The entries are sorted by their encoded keys, so equal maps always produce equal frames
+/// regardless of their iteration order.
+pub fn put_map_canonical<'a, K: 'a, V: 'a>(dst: &mut BytesMut, iter: impl ExactSizeIterator<Item = (&'a K, &'a V)>, put_key: impl Fn(&mut BytesMut, &K), put_value: impl Fn(&mut BytesMut, &V)) {
+	put_map_canonical_with_prefix(dst, iter, LengthPrefix::U32, put_key, put_value);
+}
+
+/// A utility function to put a map with a length prefix of the given width into a [`BytesMut`] in canonical order.
+pub fn put_map_canonical_with_prefix<'a, K: 'a, V: 'a>(dst: &mut BytesMut, iter: impl ExactSizeIterator<Item = (&'a K, &'a V)>, prefix: LengthPrefix, put_key: impl Fn(&mut BytesMut, &K), put_value: impl Fn(&mut BytesMut, &V)) {
+	expect_encoded(try_put_map_canonical_with_prefix(dst, iter, prefix, |dst, key| {
+		put_key(dst, key);
+		Ok(())
+	}, |dst, value| {
+		put_value(dst, value);
+		Ok(())
+	}));
+}
+
+/// A utility function to put a map with a length prefix of the given width into a [`BytesMut`] in canonical order,
+/// failing if the length does not fit into the prefix or `put_key` or `put_value` fails.
+pub fn try_put_map_canonical_with_prefix<'a, K: 'a, V: 'a>(dst: &mut BytesMut, iter: impl ExactSizeIterator<Item = (&'a K, &'a V)>, prefix: LengthPrefix, put_key: impl Fn(&mut BytesMut, &K) -> Result<(), EncodeError>, put_value: impl Fn(&mut BytesMut, &V) -> Result<(), EncodeError>) -> Result<(), EncodeError> {
+	let mut entries = iter.map(|(key, value)| {
+		let mut buf = BytesMut::new();
+		put_key(&mut buf, key)?;
+		Ok((buf, value))
+	}).collect::<Result<Vec<_>, EncodeError>>()?;
+	entries.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+
+	try_put_length(dst, entries.len(), prefix)?;
+	for (key, value) in entries {
+		dst.put_slice(&key);
+		put_value(dst, value)?;
+	}
+	Ok(())
+}
+
+/// A utility function to put whatever `put` writes into a [`BytesMut`] behind a `u32` length prefix.
+///
+/// The length is filled in once `put` returns, so the contents do not have to be measured up front.
+///
+/// # Panics
+/// Panics if the contents are longer than a `u32`, [`try_put_prefixed`] returns an error instead.
+pub fn put_prefixed(dst: &mut BytesMut, put: impl FnOnce(&mut BytesMut)) {
+	expect_encoded(try_put_prefixed(dst, |dst| {
+		put(dst);
+		Ok(())
+	}));
+}
+
+/// A utility function to put whatever `put` writes into a [`BytesMut`] behind a `u32` length prefix,
+/// failing if `put` fails or the contents are longer than a `u32`.
+pub fn try_put_prefixed(dst: &mut BytesMut, put: impl FnOnce(&mut BytesMut) -> Result<(), EncodeError>) -> Result<(), EncodeError> {
+	let start = dst.len();
+	dst.put_u32(0);
+	put(dst)?;
+	let len = dst.len() - start - 4;
+	if len > u32::MAX as usize {
+		return Err(EncodeError::LengthOverflow { length: len, prefix: LengthPrefix::U32 });
+	}
+	dst[start..start + 4].copy_from_slice(&(len as u32).to_be_bytes());
+	Ok(())
+}
+
+/// A utility function to put a [`u64`] as an unsigned LEB128 varint into a [`BytesMut`].
+pub fn put_varint_u64(dst: &mut BytesMut, mut value: u64) {
+	while value >= 0x80 {
+		dst.put_u8((value as u8 & 0x7F) | 0x80);
+		value >>= 7;
+	}
+	dst.put_u8(value as u8);
+}
+
+/// Returns the number of bytes [`put_varint_u64`] writes for `value`.
+pub fn varint_len_u64(value: u64) -> usize {
+	let bits = 64 - (value | 1).leading_zeros() as usize;
+	bits.div_ceil(7)
+}
+
+/// A utility function to put an integer as a LEB128 varint into a [`BytesMut`].
+pub fn put_varint<T: Varint>(dst: &mut BytesMut, value: T) {
+	put_varint_u64(dst, value.to_varint());
+}
+
+/// A utility function to put a signed integer as a zigzag encoded varint into a [`BytesMut`].
+pub fn put_zigzag<T: ZigZag>(dst: &mut BytesMut, value: T) {
+	put_varint_u64(dst, value.to_zigzag());
 }
 
 /// A utility function to put a [`Vec<Option>`] into a [`BytesMut`].
 pub fn put_option_array<T>(dst: &mut BytesMut, arr: &[Option<T>], put: impl Fn(&mut BytesMut, &T)) {
+	put_option_array_with_prefix(dst, arr, LengthPrefix::U32, put);
+}
+
+/// A utility function to put a [`Vec<Option>`] with a length prefix of the given width into a [`BytesMut`].
+pub fn put_option_array_with_prefix<T>(dst: &mut BytesMut, arr: &[Option<T>], prefix: LengthPrefix, put: impl Fn(&mut BytesMut, &T)) {
 	const NO_VALUE: u8 = 0;
 	const HAS_VALUE: u8 = 1;
 
-	dst.put_u32(arr.len() as u32);
+	put_length(dst, arr.len(), prefix);
 	for opt in arr {
 		match opt {
 			Some(val) => {
@@ -0,0 +1,280 @@
+use quote::format_ident;
+use syn::{ext::IdentExt, parse::{Parse, ParseStream}, punctuated::Punctuated, Attribute, Data, DeriveInput, Error, Expr, ExprLit, Ident, Lit, Path, Result, Token};
+
+use crate::tag::TagType;
+
+/// Options set on the type with `#[wire(...)]`.
+#[derive(Default)]
+pub struct ContainerAttrs {
+	/// Reject frames with bytes left over after decoding.
+	pub strict: bool,
+	/// The integer type of the variant tags of an enum.
+	pub tag: TagType,
+	/// How the fields of a struct are laid out in the frame.
+	pub layout: Layout,
+	/// Write number fields and enum tags in little-endian byte order unless a field says otherwise.
+	pub little_endian: bool,
+}
+
+/// The layout of the fields of a struct.
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum Layout {
+	/// Fields follow each other in declaration order.
+	#[default]
+	Positional,
+	/// Fields follow each other behind a length prefix, so fields can be appended without breaking older readers.
+	Versioned,
+	/// Fields are written as `id`, wire type and length behind a length prefix, so they can be added, removed and reordered.
+	Tlv,
+}
+
+impl ContainerAttrs {
+	///
+	/// A little-endian container cannot use framing that is always big-endian, which is the length prefix
+	/// of `versioned` and `tlv` structs and of the variants of an enum with a `#[wire(other)]` variant.
+	pub fn parse(input: &DeriveInput) -> Result<Self> {
+		let mut container = Self::default();
+		let mut endian = None;
+		for meta in wire_metas(&input.attrs)? {
+			match meta.name.to_string().as_str() {
+				"strict" => {
+					meta.expect_flag()?;
+					container.strict = true;
+				},
+				"tag" if matches!(input.data, Data::Enum(_)) => container.tag = TagType::parse(meta.expect_value()?)?,
+				"versioned" | "tlv" if matches!(input.data, Data::Struct(_)) => {
+					meta.expect_flag()?;
+					if container.layout != Layout::Positional {
+						return Err(Error::new_spanned(&meta.name, "`versioned` and `tlv` cannot be combined"));
+					}
+
+					container.layout = match meta.name == "tlv" {
+						true => Layout::Tlv,
+						false => Layout::Versioned,
+					};
+				},
+				"endian" => {
+					container.little_endian = match meta.expect_value()? {
+						Expr::Lit(ExprLit { lit: Lit::Str(lit), .. }) if lit.value() == "little" => true,
+						Expr::Lit(ExprLit { lit: Lit::Str(lit), .. }) if lit.value() == "big" => false,
+						value => return Err(Error::new_spanned(value, "expected `endian = \"little\"` or `endian = \"big\"`")),
+					};
+					endian = Some(meta.name);
+				},
+				_ => return Err(Error::new_spanned(&meta.name, "unknown `wire` container attribute")),
+			}
+		}
+
+		if let (Some(endian), true) = (endian, container.little_endian) {
+			if container.layout != Layout::Positional {
+				return Err(Error::new_spanned(endian, "`endian = \"little\"` cannot be combined with `versioned` or `tlv`, whose length prefixes are big-endian"));
+			}
+
+			if let Data::Enum(data) = &input.data {
+				for variant in &data.variants {
+					if VariantAttrs::parse(&variant.attrs)?.other {
+						return Err(Error::new_spanned(endian, "`endian = \"little\"` cannot be combined with a `#[wire(other)]` variant, since variants are length-prefixed in big-endian"));
+					}
+				}
+			}
+		}
+
+		Ok(container)
+	}
+}
+
+/// How a number field is written.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum NumberEncoding {
+	/// Full width in big-endian byte order, the regular `IntoFrame` encoding.
+	BigEndian,
+	/// Full width in little-endian byte order.
+	LittleEndian,
+	/// An unsigned LEB128 varint, sign-extended for signed integers.
+	Varint,
+	/// A zigzag encoded LEB128 varint for signed integers.
+	ZigZag,
+}
+
+/// Options set on an enum variant with `#[wire(...)]`.
+#[derive(Default)]
+pub struct VariantAttrs {
+	/// Decode unknown tags into this variant instead of failing.
+	pub other: bool,
+}
+
+impl VariantAttrs {
+	pub fn parse(attrs: &[Attribute]) -> Result<Self> {
+		let mut variant = Self::default();
+		for meta in wire_metas(attrs)? {
+			match meta.name.to_string().as_str() {
+				"other" => {
+					meta.expect_flag()?;
+					variant.other = true;
+				},
+				_ => return Err(Error::new_spanned(&meta.name, "unknown `wire` variant attribute")),
+			}
+		}
+
+		Ok(variant)
+	}
+}
+
+/// Options set on a field with `#[wire(...)]`.
+#[derive(Default)]
+pub struct FieldAttrs {
+	/// Leave the field out of the frame and fill it with `Default::default()` when decoding.
+	pub skip: bool,
+	/// Leave the field out of the frame and fill it by calling this function when decoding.
+	pub default: Option<Path>,
+	/// Encode the field with `fn(&T, &mut BytesMut)` instead of `IntoFrame`.
+	pub encode_with: Option<Path>,
+	/// Decode the field with `fn(&mut Frame) -> Result<T, E>` instead of `FromFrame`.
+	pub decode_with: Option<Path>,
+	/// The version of a `versioned` struct that added the field. It decodes as `Default::default()` when missing.
+	pub since: Option<u64>,
+	/// The id of the field in a `tlv` struct.
+	pub id: Option<u64>,
+	/// How the field is written if it is a number.
+	pub number: Option<NumberEncoding>,
+	/// The `LengthPrefix` variant used for the length of a string or collection.
+	pub len: Option<Ident>,
+}
+
+impl FieldAttrs {
+	pub fn parse(attrs: &[Attribute]) -> Result<Self> {
+		let mut field = Self::default();
+		for meta in wire_metas(attrs)? {
+			match meta.name.to_string().as_str() {
+				"skip" => {
+					meta.expect_flag()?;
+					field.skip = true;
+				},
+				"default" => field.default = Some(meta.expect_path()?),
+				"with" => {
+					let module = meta.expect_path()?;
+					field.encode_with = Some(syn::parse_quote! { #module::encode });
+					field.decode_with = Some(syn::parse_quote! { #module::decode });
+				},
+				"encode_with" => field.encode_with = Some(meta.expect_path()?),
+				"decode_with" => field.decode_with = Some(meta.expect_path()?),
+				"since" => field.since = Some(meta.expect_int()?),
+				"id" => field.id = Some(meta.expect_int()?),
+				"len" => field.len = Some(match meta.expect_value()? {
+					Expr::Path(path) if path.path.is_ident("u8") => format_ident!("U8"),
+					Expr::Path(path) if path.path.is_ident("u16") => format_ident!("U16"),
+					Expr::Path(path) if path.path.is_ident("u32") => format_ident!("U32"),
+					Expr::Path(path) if path.path.is_ident("u64") => format_ident!("U64"),
+					Expr::Path(path) if path.path.is_ident("varint") => format_ident!("Varint"),
+					value => return Err(Error::new_spanned(value, "expected `len = u8`, `u16`, `u32`, `u64` or `varint`")),
+				}),
+				"be" | "le" | "varint" | "zigzag" => {
+					meta.expect_flag()?;
+					if field.number.is_some() {
+						return Err(Error::new_spanned(&meta.name, "only one of `be`, `le`, `varint` and `zigzag` can be used"));
+					}
+
+					field.number = Some(match meta.name.to_string().as_str() {
+						"be" => NumberEncoding::BigEndian,
+						"le" => NumberEncoding::LittleEndian,
+						"varint" => NumberEncoding::Varint,
+						_ => NumberEncoding::ZigZag,
+					});
+				},
+				_ => return Err(Error::new_spanned(&meta.name, "unknown `wire` field attribute")),
+			}
+
+			if field.number.is_some() && (field.is_skipped() || field.encode_with.is_some() || field.decode_with.is_some()) {
+				return Err(Error::new_spanned(&meta.name, "number encodings cannot be combined with skipped fields or custom functions"));
+			}
+
+			if field.len.is_some() && (field.number.is_some() || field.is_skipped() || field.encode_with.is_some() || field.decode_with.is_some()) {
+				return Err(Error::new_spanned(&meta.name, "`len` cannot be combined with number encodings, skipped fields or custom functions"));
+			}
+
+			if field.is_skipped() && (field.encode_with.is_some() || field.decode_with.is_some()) {
+				return Err(Error::new_spanned(&meta.name, "skipped fields cannot be encoded or decoded with custom functions"));
+			}
+
+			if field.is_skipped() && field.since.is_some() {
+				return Err(Error::new_spanned(&meta.name, "skipped fields cannot have a `since` version"));
+			}
+
+			if field.is_skipped() && field.id.is_some() {
+				return Err(Error::new_spanned(&meta.name, "skipped fields cannot have an `id`"));
+			}
+		}
+
+		Ok(field)
+	}
+
+	/// Whether the field is left out of the frame.
+	pub fn is_skipped(&self) -> bool {
+		self.skip || self.default.is_some()
+	}
+}
+
+/// A single `name` or `name = value` item of a `#[wire(...)]` attribute.
+///
+/// Values are parsed as expressions, so both `tag = u16` and `default = "path"` are accepted.
+pub struct WireMeta {
+	pub name: Ident,
+	pub value: Option<Expr>,
+}
+
+impl WireMeta {
+	/// Fails if the item has a value.
+	pub fn expect_flag(&self) -> Result<()> {
+		match &self.value {
+			Some(value) => Err(Error::new_spanned(value, format!("`{}` does not take a value", self.name))),
+			None => Ok(()),
+		}
+	}
+
+	/// Returns the value of the item, failing if it has none.
+	pub fn expect_value(&self) -> Result<&Expr> {
+		self.value.as_ref().ok_or_else(|| Error::new_spanned(&self.name, format!("expected `{} = ...`", self.name)))
+	}
+
+	/// Returns the value of the item as an integer.
+	pub fn expect_int(&self) -> Result<u64> {
+		match self.expect_value()? {
+			Expr::Lit(ExprLit { lit: Lit::Int(lit), .. }) => lit.base10_parse(),
+			value => Err(Error::new_spanned(value, format!("expected `{} = <integer>`", self.name))),
+		}
+	}
+
+	/// Returns the value of the item as a path, given either as `"path"` or bare.
+	pub fn expect_path(&self) -> Result<Path> {
+		match self.expect_value()? {
+			Expr::Lit(ExprLit { lit: Lit::Str(lit), .. }) => lit.parse(),
+			Expr::Path(path) => Ok(path.path.clone()),
+			value => Err(Error::new_spanned(value, format!("expected `{} = \"path\"`", self.name))),
+		}
+	}
+}
+
+impl Parse for WireMeta {
+	fn parse(input: ParseStream) -> Result<Self> {
+		let name = input.call(Ident::parse_any)?;
+		let value = match input.peek(Token![=]) {
+			true => {
+				input.parse::<Token![=]>()?;
+				Some(input.parse()?)
+			},
+			false => None,
+		};
+
+		Ok(Self { name, value })
+	}
+}
+
+/// Collects the items of every `#[wire(...)]` attribute.
+pub fn wire_metas(attrs: &[Attribute]) -> Result<Vec<WireMeta>> {
+	let mut metas = Vec::new();
+	for attr in attrs.iter().filter(|attr| attr.path.is_ident("wire")) {
+		metas.extend(attr.parse_args_with(Punctuated::<WireMeta, Token![,]>::parse_terminated)?);
+	}
+
+	Ok(metas)
+}
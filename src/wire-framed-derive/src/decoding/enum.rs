@@ -1,53 +1,116 @@
 use proc_macro2::{TokenStream as TokenStream2};
-use syn::{DeriveInput, DataEnum, Error};
+use syn::{DeriveInput, DataEnum, Fields};
 use quote::quote;
 
-pub fn enum_impl(input: &DeriveInput, data: DataEnum) -> TokenStream2 {
+use crate::{attrs::ContainerAttrs, field, tag};
+
+pub fn enum_impl(input: &DeriveInput, data: DataEnum, target: &super::Target, container: &ContainerAttrs) -> TokenStream2 {
     // Common vars for building the final output
     let name = &input.ident;
     let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+	let trait_path = target.trait_path();
+	let frame_type = target.frame_type();
+	let parse_fn = target.parse_fn();
+	let from_frame = target.entry_point_impl();
 
-	if data.variants.len() > 255 {
-		return Error::new(name.span(), "Cannot derive `Encoding` for enum with more than 255 variants").into_compile_error().into();
-	}
+	let tags = match tag::variant_tags(&data, container.tag) {
+		Ok(tags) => tags,
+		Err(err) => return err.into_compile_error(),
+	};
+	let fallback = match tag::fallback_variant(&data) {
+		Ok(fallback) => fallback,
+		Err(err) => return err.into_compile_error(),
+	};
+	let get_tag = container.tag.get_fn(container.little_endian);
+	let tag_size = container.tag.size();
+
+	let mut kind_values = Vec::with_capacity(data.variants.len());
+	let mut frame_variant = Vec::with_capacity(data.variants.len());
+	for (index, (variant, tag)) in data.variants.iter().zip(&tags).enumerate() {
+		if fallback == Some(index) {
+			continue;
+		}
 
-	let kind_values = data.variants.iter().enumerate().map(|(i, _)| i as u8).collect::<Vec<_>>();
+		let fields = match field::parse_fields(&variant.fields, container) {
+			Ok(fields) => fields,
+			Err(err) => return err.into_compile_error(),
+		};
 
-	let frame_variant = data.variants.iter().map(|variant| {
 		let variant_name = &variant.ident;
-		let is_unit_struct = variant.fields.is_empty();
-		if is_unit_struct {
-			quote! { Self::#variant_name }
-		} else {
-			let is_tuple_struct = variant.fields.iter().next().unwrap().ident.is_none();
-			if is_tuple_struct {
-				let variant_id_iters = variant.fields.iter().enumerate().map(|(i, _)| syn::Index::from(i)).collect::<Vec<_>>();
-				quote! { Self::#variant_name(#(::wire_framed::wire_framed_core::FromFrame::parse_frame(frame).map_err(|err| ::std::io::Error::new(::std::io::ErrorKind::InvalidInput, format!("expected '{}' variant: expected '{}': {}", stringify!(#variant_name), stringify!(#variant_id_iters), err)))?,)*) }
-			} else {
-				let field_names = variant.fields.iter().map(|field| &field.ident).collect::<Vec<_>>();
-				quote! {
-					Self::#variant_name {
-						#(#field_names: ::wire_framed::wire_framed_core::FromFrame::parse_frame(frame).map_err(|err| ::std::io::Error::new(::std::io::ErrorKind::InvalidInput, format!("expected '{}' variant: expected '{}': {}", stringify!(#variant_name), stringify!(#field_names), err)))?,)*
-					}
-				}
+		let values = fields.iter().map(|field| {
+			let name = field.name();
+			field.decode(target, &quote! { |err| ::wire_framed::wire_framed_core::DecodeError::from(err).in_field(#name).in_variant(stringify!(#variant_name)) })
+		});
+		kind_values.push(container.tag.literal(*tag));
+		frame_variant.push(match &variant.fields {
+			Fields::Unit => quote! { Self::#variant_name },
+			Fields::Unnamed(_) => quote! { Self::#variant_name(#(#values),*) },
+			Fields::Named(_) => {
+				let members = fields.iter().map(|field| &field.member);
+				quote! { Self::#variant_name { #(#members: #values,)* } }
+			},
+		});
+	}
+
+	let unknown_kind = match fallback.map(|index| &data.variants[index]) {
+		Some(variant) => {
+			let variant_name = &variant.ident;
+			let rest = target.rest();
+			let values = [quote! { kind }, quote! { ::std::convert::Into::into(#rest) }];
+			let values = values.iter().take(variant.fields.len());
+			match &variant.fields {
+				Fields::Unit => quote! {{
+					let _ = #rest;
+					Self::#variant_name
+				}},
+				Fields::Unnamed(_) => quote! { Self::#variant_name(#(#values),*) },
+				Fields::Named(fields) => {
+					let members = fields.named.iter().map(|field| &field.ident);
+					quote! { Self::#variant_name { #(#members: #values,)* } }
+				},
 			}
-		}
-	}).collect::<Vec<_>>();
+		},
+		None => quote! {{
+			let kind = ::wire_framed::wire_framed_core::DecodeErrorKind::InvalidTag { name: concat!(stringify!(#name), " kind").to_string(), tag: kind as u64 };
+			return Err(::wire_framed::wire_framed_core::DecodeError::new(kind, frame.len() + #tag_size));
+		}},
+	};
 
-	quote! {
-		impl #impl_generics ::wire_framed::wire_framed_core::FromFrame for #name #ty_generics #where_clause {
-			type Error = ::std::io::Error;
-
-			fn parse_frame(frame: &mut ::wire_framed::wire_framed_core::bytes::Bytes) -> ::std::result::Result<Self, Self::Error> {
-				use ::wire_framed::wire_framed_core::bytes::Buf;
-				let kind: u8 = ::wire_framed::wire_framed_core::FromFrame::parse_frame(frame).map_err(|_| ::std::io::Error::new(::std::io::ErrorKind::InvalidInput, format!("expected '{}' kind", stringify!(#name))))?;
-				let value = match kind {
-					#(#kind_values => #frame_variant,)*
-					_ => return Err(::std::io::Error::new(::std::io::ErrorKind::InvalidInput, format!("invalid '{}' kind", stringify!(#name)))),
+	let parse_value = quote! {
+		let value = match kind {
+			#(#kind_values => #frame_variant,)*
+			_ => #unknown_kind,
+		};
+
+		Ok(value)
+	};
+	let parse_body = match fallback {
+		None => parse_value,
+		// With a fallback every variant is length-prefixed, so an unknown one takes exactly its own bytes
+		Some(_) => {
+			let body = target.prefixed(&quote! { concat!(stringify!(#name), " length") });
+			quote! {
+				let mut body = #body?;
+				let trailing = frame.len();
+				let parse_body = |frame: &mut #frame_type| -> ::std::result::Result<Self, Self::Error> {
+					#parse_value
 				};
+				parse_body(&mut body).map_err(|err| err.with_trailing(trailing))
+			}
+		},
+	};
 
-				Ok(value)
+	quote! {
+		impl #impl_generics #trait_path for #name #ty_generics #where_clause {
+			type Error = ::wire_framed::wire_framed_core::DecodeError;
+
+			fn #parse_fn(frame: &mut #frame_type) -> ::std::result::Result<Self, Self::Error> {
+				let _guard = ::wire_framed::wire_framed_core::limits::enter(frame)?;
+				let kind = #get_tag(frame, concat!(stringify!(#name), " kind"))?;
+				#parse_body
 			}
+
+			#from_frame
 		}
 	}
 }
\ No newline at end of file
@@ -4,16 +4,131 @@ use r#struct::struct_impl;
 use r#enum::enum_impl;
 
 use proc_macro::TokenStream;
-use syn::{parse_macro_input, DeriveInput, Error, Data};
+use proc_macro2::TokenStream as TokenStream2;
+use syn::{parse_macro_input, DeriveInput, Error, Data, Lifetime};
+use quote::quote;
+
+use crate::attrs::ContainerAttrs;
 
 pub fn decoding_impl(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
+	let container = match ContainerAttrs::parse(&input) {
+		Ok(container) => container,
+		Err(err) => return err.into_compile_error().into(),
+	};
+	let target = Target::new(&input, &container);
 
     let tokens = match input.data {
-        Data::Struct(ref data) => struct_impl(&input, data.clone()),
-		Data::Enum(ref data) => enum_impl(&input, data.clone()),
+        Data::Struct(ref data) => struct_impl(&input, data.clone(), &target, &container),
+		Data::Enum(ref data) => enum_impl(&input, data.clone(), &target, &container),
         _ => return Error::new(input.ident.span(), "wire-framed does not support unions").into_compile_error().into(),
     };
 
 	tokens.into()
+}
+
+/// The decoding trait implemented by the derive.
+///
+/// Types with a lifetime parameter borrow from the frame and implement `FromFrameRef`,
+/// all other types implement `FromFrame`.
+pub struct Target {
+	lifetime: Option<Lifetime>,
+	strict: bool,
+}
+
+impl Target {
+	fn new(input: &DeriveInput, container: &ContainerAttrs) -> Self {
+		Self {
+			lifetime: input.generics.lifetimes().next().map(|def| def.lifetime.clone()),
+			strict: container.strict,
+		}
+	}
+
+	/// The path of the implemented trait.
+	pub fn trait_path(&self) -> TokenStream2 {
+		match &self.lifetime {
+			None => quote! { ::wire_framed::wire_framed_core::FromFrame },
+			Some(lifetime) => quote! { ::wire_framed::wire_framed_core::FromFrameRef<#lifetime> },
+		}
+	}
+
+	/// The type of the frame being parsed.
+	pub fn frame_type(&self) -> TokenStream2 {
+		match &self.lifetime {
+			None => quote! { ::wire_framed::wire_framed_core::bytes::Bytes },
+			Some(lifetime) => quote! { &#lifetime [u8] },
+		}
+	}
+
+	/// The name of the parsing method.
+	pub fn parse_fn(&self) -> TokenStream2 {
+		match &self.lifetime {
+			None => quote! { parse_frame },
+			Some(_) => quote! { parse_frame_ref },
+		}
+	}
+
+	/// An expression parsing the next value of an inferred type from `frame`.
+	pub fn parse_value(&self) -> TokenStream2 {
+		match &self.lifetime {
+			None => quote! { ::wire_framed::wire_framed_core::FromFrame::parse_frame(frame) },
+			Some(_) => quote! { ::wire_framed::wire_framed_core::FromFrameRef::parse_frame_ref(frame) },
+		}
+	}
+
+	/// An expression taking a `u32` length-prefixed slice off `frame`.
+	pub fn prefixed(&self, name: &TokenStream2) -> TokenStream2 {
+		match &self.lifetime {
+			None => quote! { ::wire_framed::wire_framed_core::utils::get_bytes(frame, #name) },
+			Some(_) => quote! { ::wire_framed::wire_framed_core::utils::get_slice(frame, #name) },
+		}
+	}
+
+	/// An expression taking everything that is left in `frame`.
+	pub fn rest(&self) -> TokenStream2 {
+		match &self.lifetime {
+			None => quote! { frame.split_to(frame.len()) },
+			Some(_) => quote! { ::std::mem::take(frame) },
+		}
+	}
+
+	/// Generates the entry points that resolve the byte offset of decode errors within the frame.
+	///
+	/// Strict types reject trailing bytes from every entry point, not only the `exact` ones.
+	pub fn entry_point_impl(&self) -> TokenStream2 {
+		let (from_fn, from_exact_fn) = match &self.lifetime {
+			None => (quote! { from_frame_with_limits }, quote! { from_frame_exact_with_limits }),
+			Some(_) => (quote! { from_frame_ref_with_limits }, quote! { from_frame_ref_exact_with_limits }),
+		};
+		let from_body = self.entry_point_body(self.strict);
+		let from_exact_body = self.entry_point_body(true);
+		let frame_type = self.frame_type();
+
+		quote! {
+			fn #from_fn(mut frame: #frame_type, limits: ::wire_framed::wire_framed_core::DecodeLimits) -> ::std::result::Result<Self, Self::Error> {
+				#from_body
+			}
+
+			fn #from_exact_fn(mut frame: #frame_type, limits: ::wire_framed::wire_framed_core::DecodeLimits) -> ::std::result::Result<Self, Self::Error> {
+				#from_exact_body
+			}
+		}
+	}
+
+	fn entry_point_body(&self, strict: bool) -> TokenStream2 {
+		let parse_fn = self.parse_fn();
+		let check_trailing = match strict {
+			true => quote! { ::wire_framed::wire_framed_core::utils::check_trailing(&frame)?; },
+			false => quote! {},
+		};
+
+		quote! {
+			let frame_len = frame.len();
+			::wire_framed::wire_framed_core::limits::with_limits(limits, || -> ::std::result::Result<Self, ::wire_framed::wire_framed_core::DecodeError> {
+				let value = Self::#parse_fn(&mut frame)?;
+				#check_trailing
+				Ok(value)
+			}).map_err(|err| err.with_frame_len(frame_len))
+		}
+	}
 }
\ No newline at end of file
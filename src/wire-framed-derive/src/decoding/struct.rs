@@ -1,51 +1,146 @@
 use proc_macro2::TokenStream as TokenStream2;
-use syn::{DataStruct, DeriveInput};
+use syn::{DataStruct, DeriveInput, Fields};
 use quote::quote;
 
-pub fn struct_impl(input: &DeriveInput, data: DataStruct) -> TokenStream2 {
+use crate::{attrs::{ContainerAttrs, Layout}, field};
+
+pub fn struct_impl(input: &DeriveInput, data: DataStruct, target: &super::Target, container: &ContainerAttrs) -> TokenStream2 {
     // Common vars for building the final output
     let name = &input.ident;
     let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+	let trait_path = target.trait_path();
+	let frame_type = target.frame_type();
+	let parse_fn = target.parse_fn();
+	let from_frame = target.entry_point_impl();
 
 	let is_unit_struct = data.fields.is_empty();
-	if is_unit_struct {
+	if is_unit_struct && container.layout == Layout::Positional {
 		return quote! {
-			impl #impl_generics ::wire_framed::wire_framed_core::FromFrame for #name #ty_generics #where_clause {
-				type Error = ::std::io::Error;
+			impl #impl_generics #trait_path for #name #ty_generics #where_clause {
+				type Error = ::wire_framed::wire_framed_core::DecodeError;
 
-				fn parse_frame(_frame: &mut ::wire_framed::wire_framed_core::bytes::Bytes) -> ::std::result::Result<Self, Self::Error> {
+				fn #parse_fn(_frame: &mut #frame_type) -> ::std::result::Result<Self, Self::Error> {
 					Ok(Self)
 				}
+
+				#from_frame
 			}
 		};
 	}
 
-	let is_tuple_struct = data.fields.iter().next().unwrap().ident.is_none();
-	if is_tuple_struct {
-		let empty_iters = data.fields.iter().map(|_| quote! {}).collect::<Vec<_>>();
-		return quote! {
-			impl #impl_generics ::wire_framed::wire_framed_core::FromFrame for #name #ty_generics #where_clause {
-				type Error = ::std::io::Error;
+	let fields = match field::parse_fields(&data.fields, container) {
+		Ok(fields) => fields,
+		Err(err) => return err.into_compile_error(),
+	};
+
+	let values = fields.iter().map(|field| {
+		let name = field.name();
+		let map_err = quote! { |err| ::wire_framed::wire_framed_core::DecodeError::from(err).in_field(#name) };
+		match container.layout {
+			// TLV fields are decoded into their slots while reading the entries, missing ones fall back to their defaults
+			Layout::Tlv if !field.attrs.is_skipped() => {
+				let slot = &field.binding;
+				quote! { #slot.unwrap_or_default() }
+			},
+			_ => field.decode(target, &map_err),
+		}
+	});
+	let value = match data.fields {
+		Fields::Unnamed(_) => quote! { Self(#(#values),*) },
+		Fields::Unit => quote! { Self },
+		Fields::Named(_) => {
+			let members = fields.iter().map(|field| &field.member);
+			quote! { Self { #(#members: #values,)* } }
+		},
+	};
+
+	let parse_body = match container.layout {
+		Layout::Positional => quote! { Ok(#value) },
+		// Versioned structs are decoded from their length-prefixed body, skipping any fields added by later versions
+		Layout::Versioned => {
+			let body = target.prefixed(&quote! { concat!(stringify!(#name), " length") });
+			quote! {
+				let mut body = #body?;
+				let trailing = frame.len();
+				let parse_body = |frame: &mut #frame_type| -> ::std::result::Result<Self, Self::Error> {
+					Ok(#value)
+				};
+				parse_body(&mut body).map_err(|err| err.with_trailing(trailing))
+			}
+		},
+		// TLV structs are decoded entry by entry, skipping the entries of unknown ids
+		Layout::Tlv => {
+			let body = target.prefixed(&quote! { concat!(stringify!(#name), " length") });
+			let entry = target.prefixed(&quote! { "field value" });
+			let fields = fields.iter().filter(|field| !field.attrs.is_skipped()).collect::<Vec<_>>();
+			let slots = fields.iter().map(|field| {
+				let slot = &field.binding;
+				let ty = field.ty;
+				quote! { let mut #slot: ::std::option::Option<#ty> = None; }
+			});
+			let arms = fields.iter().map(|field| {
+				let name = field.name();
+				let slot = &field.binding;
+				let ty = field.ty;
+				let id = field.id();
+				let wire_type = field.wire_type();
+				let decode = field.decode(target, &quote! { |err| ::wire_framed::wire_framed_core::DecodeError::from(err).in_field(#name) });
+				quote! {
+					#id => {
+						if #slot.is_some() {
+							let kind = ::wire_framed::wire_framed_core::DecodeErrorKind::DuplicateKey { name: "field id".to_string() };
+							return Err(::wire_framed::wire_framed_core::DecodeError::new(kind, entry_len).in_field(#name));
+						}
 
-				fn parse_frame(frame: &mut ::wire_framed::wire_framed_core::bytes::Bytes) -> ::std::result::Result<Self, Self::Error> {
-					use ::wire_framed::wire_framed_core::bytes::Buf;
-					Ok(Self(#(#empty_iters ::wire_framed::wire_framed_core::FromFrame::parse_frame(frame)?),*))
+						if wire_type != #wire_type {
+							let kind = ::wire_framed::wire_framed_core::DecodeErrorKind::InvalidValue { name: "wire type".to_string(), reason: format!("expected {} but got {}", #wire_type, wire_type) };
+							return Err(::wire_framed::wire_framed_core::DecodeError::new(kind, entry_len - 2).in_field(#name));
+						}
+
+						let parse_value = |frame: &mut #frame_type| -> ::std::result::Result<#ty, ::wire_framed::wire_framed_core::DecodeError> {
+							let value = #decode;
+							::wire_framed::wire_framed_core::utils::check_trailing(frame).map_err(|err| err.in_field(#name))?;
+							Ok(value)
+						};
+						let trailing = frame.len();
+						#slot = Some(parse_value(&mut value).map_err(|err| err.with_trailing(trailing))?);
+					},
 				}
+			});
+
+			quote! {
+				let mut body = #body?;
+				let trailing = frame.len();
+				let parse_body = |frame: &mut #frame_type| -> ::std::result::Result<Self, Self::Error> {
+					#(#slots)*
+					while !frame.is_empty() {
+						let entry_len = frame.len();
+						let id = ::wire_framed::wire_framed_core::utils::get_u16(frame, "field id")?;
+						let wire_type = ::wire_framed::wire_framed_core::utils::get_u8(frame, "wire type")?;
+						let mut value = #entry?;
+						match id {
+							#(#arms)*
+							_ => {},
+						}
+					}
+
+					Ok(#value)
+				};
+				parse_body(&mut body).map_err(|err| err.with_trailing(trailing))
 			}
-		};
-	}
+		},
+	};
 
-	let field_names = data.fields.iter().map(|field| &field.ident).collect::<Vec<_>>();
 	quote! {
-		impl #impl_generics ::wire_framed::wire_framed_core::FromFrame for #name #ty_generics #where_clause {
-			type Error = ::std::io::Error;
-
-			fn parse_frame(frame: &mut ::wire_framed::wire_framed_core::bytes::Bytes) -> ::std::result::Result<Self, Self::Error> {
-				use ::wire_framed::wire_framed_core::bytes::Buf;
-				Ok(Self {
-					#(#field_names: ::wire_framed::wire_framed_core::FromFrame::parse_frame(frame).map_err(|err| ::std::io::Error::new(::std::io::ErrorKind::InvalidInput, format!("expected '{}': {}", stringify!(#field_names), err)))?,)*
-				})
+		impl #impl_generics #trait_path for #name #ty_generics #where_clause {
+			type Error = ::wire_framed::wire_framed_core::DecodeError;
+
+			fn #parse_fn(frame: &mut #frame_type) -> ::std::result::Result<Self, Self::Error> {
+				let _guard = ::wire_framed::wire_framed_core::limits::enter(frame)?;
+				#parse_body
 			}
+
+			#from_frame
 		}
 	}
 }
\ No newline at end of file
@@ -1,91 +1,122 @@
-use proc_macro2::{TokenStream as TokenStream2, Ident};
-use syn::{DeriveInput, DataEnum, Error, Type};
+use proc_macro2::TokenStream as TokenStream2;
+use syn::{DeriveInput, DataEnum, Fields, Variant};
 use quote::quote;
 
-pub fn enum_impl(input: &DeriveInput, data: DataEnum) -> TokenStream2 {
+use crate::{attrs::ContainerAttrs, field::{self, WireField}, tag};
+
+pub fn enum_impl(input: &DeriveInput, data: DataEnum, container: &ContainerAttrs) -> TokenStream2 {
     // Common vars for building the final output
     let name = &input.ident;
     let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
 
-	if data.variants.len() > 255 {
-		return Error::new(name.span(), "Cannot derive `Encoding` for enum with more than 255 variants").into_compile_error().into();
-	}
+	let tags = match tag::variant_tags(&data, container.tag) {
+		Ok(tags) => tags,
+		Err(err) => return err.into_compile_error(),
+	};
+	let fallback = match tag::fallback_variant(&data) {
+		Ok(fallback) => fallback,
+		Err(err) => return err.into_compile_error(),
+	};
+	let put_tag = container.tag.put_fn(container.little_endian);
+	let tag_size = container.tag.size();
 
-	let frame_variants = data.variants.iter().enumerate().map(|(kind, variant)| {
-		let kind = kind as u8;
-		let variant_name = &variant.ident;
-		let is_unit_struct = variant.fields.is_empty();
-		if is_unit_struct {
-			quote! { 
-				Self::#variant_name => {
-					frame.put_u8(#kind);
-				}
-			}
-		} else {
-			let is_tuple_struct = variant.fields.iter().next().unwrap().ident.is_none();
-			if is_tuple_struct {
-				let (field_names, field_types): (Vec<Ident>, Vec<Type>) = variant.fields.iter().enumerate().map(|(i, f)| (quote::format_ident!("_{}", i), f.ty.clone())).unzip();
+	let mut frame_variants = Vec::with_capacity(data.variants.len());
+	let mut encoded_len_variants = Vec::with_capacity(data.variants.len());
+	for (index, (variant, tag)) in data.variants.iter().zip(&tags).enumerate() {
+		let fields = match field::parse_fields(&variant.fields, container) {
+			Ok(fields) => fields,
+			Err(err) => return err.into_compile_error(),
+		};
 
-				quote! {
-					Self::#variant_name(#(#field_names),*) => {
-						frame.put_u8(#kind);
-						#(<#field_types as ::wire_framed::wire_framed_core::IntoFrame>::extend_frame(#field_names, frame);)*
-					}
-				}
-			} else {
-				let (field_names, field_types): (Vec<Ident>, Vec<Type>) = variant.fields.iter().map(|f| (f.ident.as_ref().unwrap().clone(), f.ty.clone())).unzip();
-				quote! {
-					Self::#variant_name { #(#field_names),* } => {
-						frame.put_u8(#kind);
-						#(<#field_types as ::wire_framed::wire_framed_core::IntoFrame>::extend_frame(#field_names, frame);)*
-					}
-				}
-			}
+		// Without the captured tag and bytes, re-encoding the fallback would corrupt a relayed frame
+		if fallback == Some(index) && fields.len() != 2 {
+			let message = "encoding a `#[wire(other)]` variant requires it to hold the tag and the bytes of the variant";
+			return syn::Error::new_spanned(&variant.ident, message).into_compile_error();
 		}
-	}).collect::<Vec<_>>();
-	
-	let size_hint_variants = data.variants.iter().map(|variant| {
-		let variant_name = &variant.ident;
-		let is_unit_struct = variant.fields.is_empty();
-		if is_unit_struct {
-			quote! { 
-				Self::#variant_name => 0
-			}
-		} else {
-			let is_tuple_struct = variant.fields.iter().next().unwrap().ident.is_none();
-			if is_tuple_struct {
-				let (field_names, field_types): (Vec<Ident>, Vec<Type>) = variant.fields.iter().enumerate().map(|(i, f)| (quote::format_ident!("_{}", i), f.ty.clone())).unzip();
 
-				quote! {
-					Self::#variant_name(#(#field_names),*) => {
-						[#(<#field_types as ::wire_framed::wire_framed_core::IntoFrame>::size_hint(#field_names)),*].iter().sum()
-					}
-				}
-			} else {
-				let (field_names, field_types): (Vec<Ident>, Vec<Type>) = variant.fields.iter().map(|f| (f.ident.as_ref().unwrap().clone(), f.ty.clone())).unzip();
-				quote! {
-					Self::#variant_name { #(#field_names),* } => {
-						[#(<#field_types as ::wire_framed::wire_framed_core::IntoFrame>::size_hint(#field_names)),*].iter().sum()
-					}
-				}
+		let pattern = variant_pattern(variant, &fields);
+		let (kind, encode_body, body_len) = match fallback == Some(index) {
+			// The fallback writes back the tag and the raw bytes it captured
+			true => {
+				let kind = &fields[0].binding;
+				let rest = &fields[1].binding;
+				let rest = quote! { ::std::convert::AsRef::<[u8]>::as_ref(#rest) };
+				let encode_body = quote! {
+					::wire_framed::wire_framed_core::utils::try_put_length(frame, #rest.len(), ::wire_framed::wire_framed_core::LengthPrefix::U32)?;
+					frame.put_slice(#rest);
+				};
+				(quote! { *#kind }, encode_body, quote! { 4 + #rest.len() })
+			},
+			false => {
+				let values = fields.iter().map(|field| {
+					let binding = &field.binding;
+					quote! { #binding }
+				}).collect::<Vec<_>>();
+				let encode_fields = fields.iter().zip(&values).map(|(field, value)| field.encode(value));
+				let field_lens = fields.iter().zip(&values).map(|(field, value)| field.encoded_len(value));
+				let kind = container.tag.literal(*tag);
+				let (encode_body, body_len) = match fallback {
+					None => (quote! { #(#encode_fields)* }, quote! { 0 #(+ #field_lens)* }),
+					// With a fallback every variant is length-prefixed, so readers can skip the ones they don't know
+					Some(_) => (quote! {
+						::wire_framed::wire_framed_core::utils::try_put_prefixed(frame, |frame| {
+							#(#encode_fields)*
+							Ok(())
+						})?;
+					}, quote! { 4 #(+ #field_lens)* }),
+				};
+				(quote! { #kind }, encode_body, body_len)
+			},
+		};
+
+		frame_variants.push(quote! {
+			#pattern => {
+				frame.#put_tag(#kind);
+				#encode_body
 			}
-		}
-	}).collect::<Vec<_>>();
+		});
+		encoded_len_variants.push(quote! {
+			#pattern => #tag_size + #body_len
+		});
+	}
 
 	quote! {
 		impl #impl_generics ::wire_framed::wire_framed_core::IntoFrame for #name #ty_generics #where_clause {
 			fn extend_frame(&self, frame: &mut ::wire_framed::wire_framed_core::bytes::BytesMut) {
+				::wire_framed::wire_framed_core::utils::expect_encoded(self.try_extend_frame(frame));
+			}
+
+			fn try_extend_frame(&self, frame: &mut ::wire_framed::wire_framed_core::bytes::BytesMut) -> ::std::result::Result<(), ::wire_framed::wire_framed_core::EncodeError> {
 				use ::wire_framed::wire_framed_core::bytes::BufMut;
 				match self {
 					#(#frame_variants),*
 				}
+				Ok(())
 			}
 
 			fn size_hint(&self) -> usize {
+				self.encoded_len()
+			}
+
+			fn encoded_len(&self) -> usize {
 				match self {
-					#(#size_hint_variants),*
+					#(#encoded_len_variants),*
 				}
 			}
 		}
 	}
+}
+
+/// A pattern binding every field of `variant` to its [`WireField::binding`].
+fn variant_pattern(variant: &Variant, fields: &[WireField]) -> TokenStream2 {
+	let variant_name = &variant.ident;
+	let bindings = fields.iter().map(|field| &field.binding);
+	match &variant.fields {
+		Fields::Unit => quote! { Self::#variant_name },
+		Fields::Unnamed(_) => quote! { Self::#variant_name(#(#bindings),*) },
+		Fields::Named(_) => {
+			let members = fields.iter().map(|field| &field.member);
+			quote! { Self::#variant_name { #(#members: #bindings),* } }
+		},
+	}
 }
\ No newline at end of file
@@ -6,12 +6,18 @@ use r#enum::enum_impl;
 use proc_macro::TokenStream;
 use syn::{parse_macro_input, DeriveInput, Error, Data};
 
+use crate::attrs::ContainerAttrs;
+
 pub fn encoding_impl(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
+	let container = match ContainerAttrs::parse(&input) {
+		Ok(container) => container,
+		Err(err) => return err.into_compile_error().into(),
+	};
 
     let tokens = match input.data {
-        Data::Struct(ref data) => struct_impl(&input, data.clone()),
-		Data::Enum(ref data) => enum_impl(&input, data.clone()),
+        Data::Struct(ref data) => struct_impl(&input, data.clone(), &container),
+		Data::Enum(ref data) => enum_impl(&input, data.clone(), &container),
         _ => return Error::new(input.ident.span(), "wire-framed does not support unions").into_compile_error().into(),
     };
 
@@ -2,13 +2,19 @@ use proc_macro2::TokenStream as TokenStream2;
 use syn::{DataStruct, DeriveInput};
 use quote::quote;
 
-pub fn struct_impl(input: &DeriveInput, data: DataStruct) -> TokenStream2 {
+use crate::{attrs::{ContainerAttrs, Layout}, field};
+
+pub fn struct_impl(input: &DeriveInput, data: DataStruct, container: &ContainerAttrs) -> TokenStream2 {
     // Common vars for building the final output
     let name = &input.ident;
     let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
 
-	let is_unit_struct = data.fields.is_empty();
-	if is_unit_struct {
+	let fields = match field::parse_fields(&data.fields, container) {
+		Ok(fields) => fields,
+		Err(err) => return err.into_compile_error(),
+	};
+
+	if field::all_skipped(&fields) && container.layout == Layout::Positional {
 		return quote! {
 			impl #impl_generics ::wire_framed::wire_framed_core::IntoFrame for #name #ty_generics #where_clause {
 				fn extend_frame(&self, _frame: &mut ::wire_framed::wire_framed_core::bytes::BytesMut) {}
@@ -16,37 +22,77 @@ pub fn struct_impl(input: &DeriveInput, data: DataStruct) -> TokenStream2 {
 				fn size_hint(&self) -> usize {
 					0
 				}
+
+				fn encoded_len(&self) -> usize {
+					0
+				}
 			}
 		};
 	}
 
-	let is_tuple_struct = data.fields.iter().next().unwrap().ident.is_none();
-	if is_tuple_struct {
-		let unnamed_fields = data.fields.iter().enumerate().map(|(i, _)| syn::Index::from(i)).map(|i| quote! { self.#i }).collect::<Vec<_>>();
-		return quote! {
-			impl #impl_generics ::wire_framed::wire_framed_core::IntoFrame for #name #ty_generics #where_clause {
-				fn extend_frame(&self, frame: &mut ::wire_framed::wire_framed_core::bytes::BytesMut) {
-					use ::wire_framed::wire_framed_core::bytes::BufMut;
-					#(::wire_framed::wire_framed_core::IntoFrame::extend_frame(&#unnamed_fields, frame);)*
-				}
+	let values = fields.iter().map(|field| {
+		let member = &field.member;
+		quote! { &self.#member }
+	}).collect::<Vec<_>>();
+	let encode_fields = fields.iter().zip(&values).map(|(field, value)| field.encode(value));
+	let field_lens = fields.iter().zip(&values).map(|(field, value)| field.encoded_len(value));
 
-				fn size_hint(&self) -> usize {
-					[#(::wire_framed::wire_framed_core::IntoFrame::size_hint(&#unnamed_fields)),*].iter().sum()
+	let (encode_fields, encoded_len) = match container.layout {
+		Layout::Positional => (quote! {
+			#(#encode_fields)*
+			Ok(())
+		}, quote! { 0usize #(+ #field_lens)* }),
+		// Versioned structs are prefixed with their length, which is patched in once the fields are written
+		Layout::Versioned => (quote! {
+			::wire_framed::wire_framed_core::utils::try_put_prefixed(frame, |frame| {
+				#(#encode_fields)*
+				Ok(())
+			})
+		}, quote! { 4usize #(+ #field_lens)* }),
+		// Every field of a TLV struct is preceded by its id, wire type and length
+		Layout::Tlv => {
+			let fields = fields.iter().zip(&values).filter(|(field, _)| !field.attrs.is_skipped()).collect::<Vec<_>>();
+			let encode_fields = fields.iter().map(|(field, value)| {
+				let id = field.id();
+				let wire_type = field.wire_type();
+				let encode = field.encode(value);
+				quote! {
+					frame.put_u16(#id);
+					frame.put_u8(#wire_type);
+					::wire_framed::wire_framed_core::utils::try_put_prefixed(frame, |frame| {
+						#encode
+						Ok(())
+					})?;
 				}
-			}
-		};
-	}
+			});
+			let field_lens = fields.iter().map(|(field, value)| field.encoded_len(value));
+
+			(quote! {
+				use ::wire_framed::wire_framed_core::bytes::BufMut;
+				::wire_framed::wire_framed_core::utils::try_put_prefixed(frame, |frame| {
+					#(#encode_fields)*
+					Ok(())
+				})
+			}, quote! { 4usize #(+ 7 + #field_lens)* })
+		},
+	};
 
-	let field_names = data.fields.iter().map(|field| &field.ident).collect::<Vec<_>>();
 	quote! {
 		impl #impl_generics ::wire_framed::wire_framed_core::IntoFrame for #name #ty_generics #where_clause {
 			fn extend_frame(&self, frame: &mut ::wire_framed::wire_framed_core::bytes::BytesMut) {
-				use ::wire_framed::wire_framed_core::bytes::BufMut;
-				#(::wire_framed::wire_framed_core::IntoFrame::extend_frame(&self.#field_names, frame);)*
+				::wire_framed::wire_framed_core::utils::expect_encoded(self.try_extend_frame(frame));
+			}
+
+			fn try_extend_frame(&self, frame: &mut ::wire_framed::wire_framed_core::bytes::BytesMut) -> ::std::result::Result<(), ::wire_framed::wire_framed_core::EncodeError> {
+				#encode_fields
 			}
 			
 			fn size_hint(&self) -> usize {
-				[#(::wire_framed::wire_framed_core::IntoFrame::size_hint(&self.#field_names)),*].iter().sum()
+				self.encoded_len()
+			}
+
+			fn encoded_len(&self) -> usize {
+				#encoded_len
 			}
 		}
 	}
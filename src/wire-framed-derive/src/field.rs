@@ -0,0 +1,230 @@
+use proc_macro2::{Ident, TokenStream as TokenStream2};
+use syn::{Error, Fields, Index, Member, Result, Type};
+use quote::{format_ident, quote};
+
+use crate::{attrs::{ContainerAttrs, FieldAttrs, Layout, NumberEncoding}, decoding::Target};
+
+/// A field of a struct or enum variant together with its `#[wire(...)]` options.
+pub struct WireField<'a> {
+	/// The field name, or its index in a tuple struct.
+	pub member: Member,
+	/// The name the field is bound to when destructuring an enum variant.
+	pub binding: Ident,
+	pub ty: &'a Type,
+	pub attrs: FieldAttrs,
+}
+
+/// Parses the fields of a struct or variant.
+///
+/// Only `versioned` structs may have `since` fields, and those have to come last in ascending order.
+/// Every field of a `tlv` struct that is not skipped needs a unique `id`, and no other field may have one.
+/// Number fields without their own encoding follow the `endian` of the container. A little-endian container
+/// rejects other fields that would still be written in big-endian, unless they have their own encode functions.
+pub fn parse_fields<'a>(fields: &'a Fields, container: &ContainerAttrs) -> Result<Vec<WireField<'a>>> {
+	let layout = container.layout;
+	let fields = fields.iter().enumerate().map(|(i, field)| {
+		let mut attrs = FieldAttrs::parse(&field.attrs)?;
+		if attrs.number.is_none() && container.little_endian && is_number(&field.ty) {
+			attrs.number = Some(NumberEncoding::LittleEndian);
+		}
+
+		let custom = attrs.encode_with.is_some() || attrs.decode_with.is_some();
+		if container.little_endian && attrs.number.is_none() && !custom && !attrs.is_skipped() && !is_single_byte(&field.ty) {
+			let message = "`endian = \"little\"` only applies to primitive number fields, so this field would be big-endian; use `encode_with` and `decode_with`, or `with`";
+			return Err(Error::new_spanned(&field.ty, message));
+		}
+
+		Ok(WireField {
+			member: match &field.ident {
+				Some(ident) => Member::Named(ident.clone()),
+				None => Member::Unnamed(Index::from(i)),
+			},
+			binding: format_ident!("_{}", i),
+			ty: &field.ty,
+			attrs,
+		})
+	}).collect::<Result<Vec<_>>>()?;
+
+	let mut latest = None;
+	for field in fields.iter().filter(|field| !field.attrs.is_skipped()) {
+		match (field.attrs.since, latest) {
+			(Some(_), _) if layout != Layout::Versioned => return Err(Error::new_spanned(&field.member, "`since` requires `#[wire(versioned)]` on the struct")),
+			(Some(since), Some(latest)) if since < latest => return Err(Error::new_spanned(&field.member, format!("fields must be in version order, but a field before this one is from version {}", latest))),
+			(None, Some(_)) => return Err(Error::new_spanned(&field.member, "fields without `since` must come before all fields with `since`")),
+			_ => latest = field.attrs.since.or(latest),
+		}
+	}
+
+	let mut ids = Vec::new();
+	for field in &fields {
+		match (field.attrs.id, layout) {
+			(Some(_), layout) if layout != Layout::Tlv => return Err(Error::new_spanned(&field.member, "`id` requires `#[wire(tlv)]` on the struct")),
+			(None, Layout::Tlv) if !field.attrs.is_skipped() => return Err(Error::new_spanned(&field.member, "fields of a `tlv` struct need an `#[wire(id = ...)]`")),
+			(Some(id), _) if id > u16::MAX as u64 => return Err(Error::new_spanned(&field.member, format!("id {} does not fit into `u16`", id))),
+			(Some(id), _) if ids.contains(&id) => return Err(Error::new_spanned(&field.member, format!("id {} is already used", id))),
+			(Some(id), _) => ids.push(id),
+			_ => {},
+		}
+	}
+
+	Ok(fields)
+}
+
+/// Whether `ty` is one of the primitive number types.
+fn is_number(ty: &Type) -> bool {
+	const NUMBERS: &[&str] = &["u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize", "f32", "f64"];
+	match ty {
+		Type::Path(path) if path.qself.is_none() => NUMBERS.iter().any(|number| path.path.is_ident(number)),
+		_ => false,
+	}
+}
+
+/// Whether `ty` is written as single bytes, which have no byte order.
+fn is_single_byte(ty: &Type) -> bool {
+	match ty {
+		Type::Path(path) if path.qself.is_none() => ["u8", "i8", "bool"].iter().any(|byte| path.path.is_ident(byte)),
+		Type::Array(array) => is_single_byte(&array.elem),
+		_ => false,
+	}
+}
+
+/// Whether no field is written to or read from the frame.
+pub fn all_skipped(fields: &[WireField]) -> bool {
+	fields.iter().all(|field| field.attrs.is_skipped())
+}
+
+impl WireField<'_> {
+	/// The name of the field in error paths.
+	pub fn name(&self) -> TokenStream2 {
+		let member = &self.member;
+		quote! { stringify!(#member) }
+	}
+
+	/// Statements writing `value`, a reference to the field, into `frame`.
+	///
+	/// Errors are propagated with `?`, so the statements belong in a function returning `Result<_, EncodeError>`.
+	pub fn encode(&self, value: &TokenStream2) -> TokenStream2 {
+		let ty = self.ty;
+		if self.attrs.is_skipped() {
+			return quote! {};
+		}
+
+		if let Some(prefix) = self.length_prefix() {
+			return quote! { <#ty as ::wire_framed::wire_framed_core::utils::LengthPrefixed>::try_put_with_prefix(#value, frame, #prefix)?; };
+		}
+
+		match (&self.attrs.encode_with, self.attrs.number) {
+			(Some(encode), _) => quote! { #encode(#value, frame); },
+			(None, Some(NumberEncoding::LittleEndian)) => quote! { <#ty as ::wire_framed::wire_framed_core::utils::LittleEndian>::put_le(#value, frame); },
+			(None, Some(NumberEncoding::Varint)) => quote! { ::wire_framed::wire_framed_core::utils::put_varint::<#ty>(frame, *#value); },
+			(None, Some(NumberEncoding::ZigZag)) => quote! { ::wire_framed::wire_framed_core::utils::put_zigzag::<#ty>(frame, *#value); },
+			(None, None | Some(NumberEncoding::BigEndian)) => quote! { <#ty as ::wire_framed::wire_framed_core::IntoFrame>::try_extend_frame(#value, frame)?; },
+		}
+	}
+
+	/// An expression of the number of bytes [`WireField::encode`] writes.
+	///
+	/// Fields with a custom encode function are measured by encoding them into a scratch buffer.
+	pub fn encoded_len(&self, value: &TokenStream2) -> TokenStream2 {
+		let ty = self.ty;
+		if self.attrs.is_skipped() {
+			return quote! { 0 };
+		}
+
+		if let Some(prefix) = self.length_prefix() {
+			return quote! { <#ty as ::wire_framed::wire_framed_core::utils::LengthPrefixed>::encoded_len_with_prefix(#value, #prefix) };
+		}
+
+		match (&self.attrs.encode_with, self.attrs.number) {
+			(Some(encode), _) => quote! {{
+				let mut frame = ::wire_framed::wire_framed_core::bytes::BytesMut::new();
+				#encode(#value, &mut frame);
+				frame.len()
+			}},
+			(None, Some(NumberEncoding::Varint)) => quote! {
+				::wire_framed::wire_framed_core::utils::varint_len_u64(<#ty as ::wire_framed::wire_framed_core::utils::Varint>::to_varint(*#value))
+			},
+			(None, Some(NumberEncoding::ZigZag)) => quote! {
+				::wire_framed::wire_framed_core::utils::varint_len_u64(<#ty as ::wire_framed::wire_framed_core::utils::ZigZag>::to_zigzag(*#value))
+			},
+			(None, _) => quote! { <#ty as ::wire_framed::wire_framed_core::IntoFrame>::encoded_len(#value) },
+		}
+	}
+
+	/// The `id` of the field in a `tlv` struct as a `u16` literal.
+	pub fn id(&self) -> TokenStream2 {
+		let id = self.attrs.id.unwrap_or_default() as u16;
+		quote! { #id }
+	}
+
+	/// The wire type written before the field in a `tlv` struct, telling readers how its value is encoded.
+	///
+	/// Wire type `0` is the regular `IntoFrame` encoding of the field, `1` little-endian, `2` varint and `3` zigzag.
+	/// Strings and collections with a `u8`, `u16`, `u64` or varint length prefix use `4` to `7`.
+	pub fn wire_type(&self) -> TokenStream2 {
+		let wire_type: u8 = match (self.attrs.number, self.attrs.len.as_ref().map(Ident::to_string).as_deref()) {
+			(_, Some("U8")) => 4,
+			(_, Some("U16")) => 5,
+			(_, Some("U64")) => 6,
+			(_, Some("Varint")) => 7,
+			(Some(NumberEncoding::LittleEndian), _) => 1,
+			(Some(NumberEncoding::Varint), _) => 2,
+			(Some(NumberEncoding::ZigZag), _) => 3,
+			_ => 0,
+		};
+		quote! { #wire_type }
+	}
+
+	/// The `LengthPrefix` of a string or collection with `#[wire(len = ...)]`.
+	fn length_prefix(&self) -> Option<TokenStream2> {
+		let prefix = self.attrs.len.as_ref()?;
+		Some(quote! { ::wire_framed::wire_framed_core::LengthPrefix::#prefix })
+	}
+
+	/// An expression decoding the field from `frame`, passing errors through `map_err`.
+	pub fn decode(&self, target: &Target, map_err: &TokenStream2) -> TokenStream2 {
+		if let Some(default) = &self.attrs.default {
+			return quote! { #default() };
+		}
+
+		if self.attrs.skip {
+			return quote! { ::std::default::Default::default() };
+		}
+
+		let ty = self.ty;
+		let value = match (&self.attrs.decode_with, self.attrs.number) {
+			(Some(decode), _) => quote! { #decode(frame).map_err(#map_err)? },
+			(None, _) if self.attrs.len.is_some() => {
+				let prefix = self.length_prefix();
+				quote! {
+					<#ty as ::wire_framed::wire_framed_core::utils::FromLengthPrefixed<_>>::get_with_prefix(frame, stringify!(#ty), #prefix).map_err(#map_err)?
+				}
+			},
+			(None, Some(NumberEncoding::LittleEndian)) => quote! {
+				<#ty as ::wire_framed::wire_framed_core::utils::LittleEndian>::get_le(frame, stringify!(#ty)).map_err(#map_err)?
+			},
+			(None, Some(NumberEncoding::Varint)) => quote! {
+				::wire_framed::wire_framed_core::utils::get_varint::<_, #ty>(frame, stringify!(#ty)).map_err(#map_err)?
+			},
+			(None, Some(NumberEncoding::ZigZag)) => quote! {
+				::wire_framed::wire_framed_core::utils::get_zigzag::<_, #ty>(frame, stringify!(#ty)).map_err(#map_err)?
+			},
+			(None, None | Some(NumberEncoding::BigEndian)) => {
+				let parse_value = target.parse_value();
+				quote! { #parse_value.map_err(#map_err)? }
+			},
+		};
+
+		// Fields added in a later version are missing from frames written by older versions
+		match self.attrs.since {
+			Some(_) => quote! {
+				if frame.is_empty() {
+					::std::default::Default::default()
+				} else {
+					#value
+				}
+			},
+			None => value,
+		}
+	}
+}
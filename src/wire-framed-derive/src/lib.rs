@@ -1,16 +1,19 @@
+mod attrs;
+mod field;
+mod tag;
 mod encoding;
 mod decoding;
 
 use proc_macro::TokenStream;
 
 /// Implements the `IntoFrame` traits for the type.
-#[proc_macro_derive(Encoding)]
+#[proc_macro_derive(Encoding, attributes(wire))]
 pub fn encoding(input: TokenStream) -> TokenStream {
     encoding::encoding_impl(input)
 }
 
 /// Implements the `FromFrame` traits for the type.
-#[proc_macro_derive(Decoding)]
+#[proc_macro_derive(Decoding, attributes(wire))]
 pub fn decoding(input: TokenStream) -> TokenStream {
     decoding::decoding_impl(input)
 }
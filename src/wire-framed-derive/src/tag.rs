@@ -0,0 +1,140 @@
+use proc_macro2::{Literal, TokenStream as TokenStream2};
+use syn::{DataEnum, Error, Expr, ExprLit, Lit, Result};
+use quote::quote;
+
+use crate::attrs::VariantAttrs;
+
+/// The integer type of the variant tags of an enum, set with `#[wire(tag = u16)]`.
+#[derive(Clone, Copy, Default)]
+pub enum TagType {
+	#[default]
+	U8,
+	U16,
+	U32,
+}
+
+impl TagType {
+	pub fn parse(expr: &Expr) -> Result<Self> {
+		match expr {
+			Expr::Path(path) if path.path.is_ident("u8") => Ok(Self::U8),
+			Expr::Path(path) if path.path.is_ident("u16") => Ok(Self::U16),
+			Expr::Path(path) if path.path.is_ident("u32") => Ok(Self::U32),
+			_ => Err(Error::new_spanned(expr, "expected `u8`, `u16` or `u32`")),
+		}
+	}
+
+	fn name(self) -> &'static str {
+		match self {
+			Self::U8 => "u8",
+			Self::U16 => "u16",
+			Self::U32 => "u32",
+		}
+	}
+
+	fn max(self) -> u64 {
+		match self {
+			Self::U8 => u8::MAX as u64,
+			Self::U16 => u16::MAX as u64,
+			Self::U32 => u32::MAX as u64,
+		}
+	}
+
+	/// The number of bytes a tag takes up in the frame.
+	pub fn size(self) -> usize {
+		match self {
+			Self::U8 => 1,
+			Self::U16 => 2,
+			Self::U32 => 4,
+		}
+	}
+
+	/// A typed literal of `tag`, usable both as an expression and as a pattern.
+	pub fn literal(self, tag: u64) -> Literal {
+		match self {
+			Self::U8 => Literal::u8_suffixed(tag as u8),
+			Self::U16 => Literal::u16_suffixed(tag as u16),
+			Self::U32 => Literal::u32_suffixed(tag as u32),
+		}
+	}
+
+	/// The `BufMut` method writing a tag.
+	pub fn put_fn(self, little_endian: bool) -> TokenStream2 {
+		match (self, little_endian) {
+			(Self::U8, _) => quote! { put_u8 },
+			(Self::U16, false) => quote! { put_u16 },
+			(Self::U16, true) => quote! { put_u16_le },
+			(Self::U32, false) => quote! { put_u32 },
+			(Self::U32, true) => quote! { put_u32_le },
+		}
+	}
+
+	/// The `utils` function reading a tag.
+	pub fn get_fn(self, little_endian: bool) -> TokenStream2 {
+		match (self, little_endian) {
+			(Self::U8, _) => quote! { ::wire_framed::wire_framed_core::utils::get_u8 },
+			(Self::U16, false) => quote! { ::wire_framed::wire_framed_core::utils::get_u16 },
+			(Self::U16, true) => quote! { <u16 as ::wire_framed::wire_framed_core::utils::LittleEndian>::get_le },
+			(Self::U32, false) => quote! { ::wire_framed::wire_framed_core::utils::get_u32 },
+			(Self::U32, true) => quote! { <u32 as ::wire_framed::wire_framed_core::utils::LittleEndian>::get_le },
+		}
+	}
+}
+
+/// Assigns a tag to every variant.
+///
+/// Explicit discriminants are used as tags, and the other variants count up from the previous one,
+/// starting at zero, just like Rust discriminants do.
+pub fn variant_tags(data: &DataEnum, tag_type: TagType) -> Result<Vec<u64>> {
+	let mut tags: Vec<u64> = Vec::with_capacity(data.variants.len());
+	let mut next = Some(0);
+	for variant in &data.variants {
+		let tag = match &variant.discriminant {
+			Some((_, expr)) => parse_discriminant(expr)?,
+			None => next.ok_or_else(|| Error::new_spanned(&variant.ident, "tag overflows `u64`"))?,
+		};
+
+		if tag > tag_type.max() {
+			return Err(Error::new_spanned(&variant.ident, format!("tag {} does not fit into `{}`, consider `#[wire(tag = ...)]`", tag, tag_type.name())));
+		}
+
+		if let Some(index) = tags.iter().position(|other| *other == tag) {
+			return Err(Error::new_spanned(&variant.ident, format!("tag {} is already used by `{}`", tag, data.variants[index].ident)));
+		}
+
+		tags.push(tag);
+		next = tag.checked_add(1);
+	}
+
+	Ok(tags)
+}
+
+/// Finds the `#[wire(other)]` variant that unknown tags decode into.
+///
+/// The variant can have up to two fields: the unknown tag, followed by the bytes of the variant.
+pub fn fallback_variant(data: &DataEnum) -> Result<Option<usize>> {
+	let mut fallback = None;
+	for (index, variant) in data.variants.iter().enumerate() {
+		if !VariantAttrs::parse(&variant.attrs)?.other {
+			continue;
+		}
+
+		if fallback.is_some() {
+			return Err(Error::new_spanned(&variant.ident, "only one variant can be `#[wire(other)]`"));
+		}
+
+		if variant.fields.len() > 2 {
+			return Err(Error::new_spanned(&variant.fields, "a `#[wire(other)]` variant can only hold the tag and the bytes of the variant"));
+		}
+
+		fallback = Some(index);
+	}
+
+	Ok(fallback)
+}
+
+fn parse_discriminant(expr: &Expr) -> Result<u64> {
+	match expr {
+		Expr::Lit(ExprLit { lit: Lit::Int(int), .. }) => int.base10_parse(),
+		_ => Err(Error::new_spanned(expr, "only integer literal discriminants are supported")),
+	}
+}
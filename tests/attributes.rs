@@ -0,0 +1,398 @@
+use std::{collections::{BTreeMap, BTreeSet, HashMap, HashSet}, time::Instant};
+use wire_framed::prelude::*;
+
+#[derive(Debug, Encoding, Decoding)]
+pub struct Cached {
+    pub id: u16,
+    #[wire(skip)]
+    pub hits: u32,
+    #[wire(default = "Instant::now")]
+    pub loaded_at: Instant,
+    #[wire(with = "hex")]
+    pub digest: String,
+    #[wire(encode_with = "put_celsius", decode_with = "get_celsius")]
+    pub temperature: f64,
+}
+
+#[derive(Debug, Encoding, Decoding, PartialEq)]
+pub enum Reading {
+    Raw(#[wire(skip)] u8, u16),
+    Scaled {
+        #[wire(with = "hex")]
+        label: String,
+        #[wire(decode_with = "get_celsius", encode_with = "put_celsius")]
+        value: f64,
+    },
+}
+
+#[derive(Debug, Encoding, Decoding, Clone, PartialEq)]
+#[wire(endian = "little")]
+pub struct Sample {
+    pub sensor: u16,
+    #[wire(be)]
+    pub port: u16,
+    #[wire(varint)]
+    pub serial: u64,
+    #[wire(zigzag)]
+    pub offset: i32,
+    pub value: f32,
+    pub flags: [u8; 2],
+}
+
+#[derive(Debug, Encoding, Decoding, PartialEq, Eq)]
+#[wire(endian = "little", tag = u16)]
+#[repr(u16)]
+pub enum Command {
+    Reset = 0x0102,
+    Seek(#[wire(varint)] i16, u32),
+}
+
+#[derive(Debug, Encoding, Decoding, Clone, PartialEq, Eq)]
+pub struct Packet {
+    #[wire(len = u8)]
+    pub name: String,
+    #[wire(len = u16)]
+    pub payload: Vec<u8>,
+    #[wire(len = varint)]
+    pub tags: BTreeSet<u16>,
+    #[wire(len = u64)]
+    pub ids: Vec<u32>,
+}
+
+#[derive(Debug, Encoding, Decoding, Clone, PartialEq, Eq)]
+pub struct PacketRef<'a> {
+    #[wire(len = u8)]
+    pub name: &'a str,
+    #[wire(len = varint)]
+    pub payload: &'a [u8],
+    #[wire(len = u16)]
+    pub parts: Vec<&'a str>,
+}
+
+#[derive(Debug, Encoding, Decoding, Clone, PartialEq, Eq)]
+pub struct Directory {
+    #[wire(len = u8)]
+    pub owners: HashMap<u16, String>,
+    #[wire(len = varint)]
+    pub paths: BTreeMap<String, u32>,
+    #[wire(len = u16)]
+    pub slots: Vec<Option<u8>>,
+}
+
+#[derive(Debug, Encoding, Decoding, Clone, PartialEq, Eq)]
+pub struct DirectoryRef<'a> {
+    #[wire(len = u8)]
+    pub paths: BTreeMap<&'a str, u8>,
+    #[wire(len = u16)]
+    pub names: HashSet<&'a str>,
+}
+
+/// The first version of a versioned struct.
+pub mod v1 {
+    use wire_framed::prelude::*;
+
+    #[derive(Debug, Encoding, Decoding, Clone, PartialEq, Eq)]
+    #[wire(versioned)]
+    pub struct Profile {
+        pub id: u32,
+        pub name: String,
+    }
+
+    #[derive(Debug, Encoding, Decoding, Clone, PartialEq, Eq)]
+    #[wire(tlv)]
+    pub struct Account {
+        #[wire(id = 1)]
+        pub id: u32,
+        #[wire(id = 2)]
+        pub name: String,
+        #[wire(id = 3)]
+        pub legacy: bool,
+    }
+}
+
+/// The same struct after two releases added fields to it.
+pub mod v3 {
+    use wire_framed::prelude::*;
+
+    #[derive(Debug, Encoding, Decoding, Clone, PartialEq, Eq)]
+    #[wire(versioned)]
+    pub struct Profile<'a> {
+        pub id: u32,
+        pub name: &'a str,
+        #[wire(since = 2)]
+        pub email: Option<&'a str>,
+        #[wire(since = 3)]
+        pub tags: Vec<u16>,
+    }
+
+    #[derive(Debug, Encoding, Decoding, Clone, PartialEq, Eq)]
+    #[wire(tlv)]
+    pub struct Account<'a> {
+        #[wire(id = 2)]
+        pub name: &'a str,
+        #[wire(id = 1)]
+        pub id: u32,
+        #[wire(skip)]
+        pub verified: bool,
+        #[wire(id = 4)]
+        pub roles: Vec<&'a str>,
+    }
+}
+
+/// Encodes a hex string as its raw bytes.
+mod hex {
+    use wire_framed::prelude::*;
+
+    pub fn encode(value: &str, frame: &mut BytesMut) {
+        let bytes = (0..value.len()).step_by(2).map(|i| u8::from_str_radix(&value[i..i + 2], 16).unwrap()).collect::<Vec<_>>();
+        bytes.extend_frame(frame);
+    }
+
+    pub fn decode(frame: &mut Bytes) -> Result<String, DecodeError> {
+        let bytes = Vec::<u8>::parse_frame(frame)?;
+        Ok(bytes.iter().map(|byte| format!("{:02x}", byte)).collect())
+    }
+}
+
+/// Encodes a temperature in hundredths of a degree.
+fn put_celsius(value: &f64, frame: &mut BytesMut) {
+    frame.put_i32((value * 100.0).round() as i32);
+}
+
+fn get_celsius(frame: &mut Bytes) -> Result<f64, DecodeError> {
+    Ok(utils::get_i32(frame, "celsius")? as f64 / 100.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn field_attributes() {
+        let value = Cached {
+            id: 3,
+            hits: 99,
+            loaded_at: Instant::now(),
+            digest: "c0ffee".to_string(),
+            temperature: -4.25,
+        };
+        let frame = value.into_frame();
+        assert_eq!(&frame[..], &[0, 3, 0, 0, 0, 3, 0xC0, 0xFF, 0xEE, 0xFF, 0xFF, 0xFE, 0x57]);
+        assert_eq!(value.encoded_len(), frame.len());
+
+        let decoded = Cached::from_frame(frame).unwrap();
+        assert_eq!(decoded.id, 3);
+        assert_eq!(decoded.hits, 0);
+        assert!(decoded.loaded_at >= value.loaded_at);
+        assert_eq!(decoded.digest, "c0ffee");
+        assert_eq!(decoded.temperature, -4.25);
+
+        let err = Cached::from_frame(Bytes::from_static(&[0, 3, 0, 0, 0, 0, 0xFF])).unwrap_err();
+        assert_eq!(err.path(), &[PathSegment::Field("temperature")]);
+    }
+
+    #[test]
+    fn variant_field_attributes() {
+        let frame = Reading::Raw(7, 300).into_frame();
+        assert_eq!(&frame[..], &[0, 1, 44]);
+        assert_eq!(Reading::from_frame(frame).unwrap(), Reading::Raw(0, 300));
+
+        let value = Reading::Scaled { label: "ab".to_string(), value: 21.5 };
+        let frame = value.into_frame();
+        assert_eq!(&frame[..], &[1, 0, 0, 0, 1, 0xAB, 0, 0, 0x08, 0x66]);
+        assert_eq!(value.encoded_len(), frame.len());
+        assert_eq!(Reading::from_frame(frame).unwrap(), value);
+    }
+
+    #[test]
+    fn versioned_structs() {
+        let old = v1::Profile { id: 1, name: "ann".to_string() };
+        let new = v3::Profile { id: 2, name: "bob", email: Some("b@x"), tags: vec![7, 8] };
+
+        let frame = old.into_frame();
+        assert_eq!(&frame[..4], &[0, 0, 0, 11]);
+        assert_eq!(old.encoded_len(), frame.len());
+        let upgraded = v3::Profile::from_frame_ref(&frame).unwrap();
+        assert_eq!(upgraded, v3::Profile { id: 1, name: "ann", email: None, tags: vec![] });
+
+        let frame = new.into_frame();
+        assert_eq!(new.encoded_len(), frame.len());
+        assert_eq!(v1::Profile::from_frame_exact(frame.clone()).unwrap(), v1::Profile { id: 2, name: "bob".to_string() });
+        assert_eq!(v3::Profile::from_frame_ref(&frame).unwrap(), new);
+
+        // Older readers skip the unknown fields of every element in a sequence
+        let frame = vec![new.clone(), new.clone()].into_frame();
+        let list = Vec::<v1::Profile>::from_frame_exact(frame).unwrap();
+        assert_eq!(list.len(), 2);
+        assert_eq!(list[1].name, "bob");
+
+        let mut frame = old.into_frame().to_vec();
+        frame[3] = 5;
+        let err = v1::Profile::from_frame(frame.into()).unwrap_err();
+        assert_eq!(err.path(), &[PathSegment::Field("name")]);
+        assert_eq!(err.offset(), Some(8));
+    }
+
+    #[test]
+    fn tlv_structs() {
+        let old = v1::Account { id: 7, name: "ann".to_string(), legacy: true };
+        let frame = old.into_frame();
+        assert_eq!(&frame[..18], &[0, 0, 0, 33, 0, 1, 0, 0, 0, 0, 4, 0, 0, 0, 7, 0, 2, 0]);
+        assert_eq!(old.encoded_len(), frame.len());
+        assert_eq!(v1::Account::from_frame(frame.clone()).unwrap(), old);
+
+        // Unknown ids are skipped, missing ones are filled with their defaults and the order does not matter
+        let upgraded = v3::Account::from_frame_ref(&frame).unwrap();
+        assert_eq!(upgraded, v3::Account { name: "ann", id: 7, verified: false, roles: vec![] });
+
+        let new = v3::Account { name: "bob", id: 9, verified: true, roles: vec!["admin"] };
+        let frame = new.into_frame();
+        assert_eq!(new.encoded_len(), frame.len());
+        assert_eq!(v3::Account::from_frame_ref(&frame).unwrap(), v3::Account { verified: false, ..new.clone() });
+        assert_eq!(v1::Account::from_frame_exact(frame).unwrap(), v1::Account { id: 9, name: "bob".to_string(), legacy: false });
+
+        let mut frame = old.into_frame().to_vec();
+        frame.extend_from_slice(&[0, 1, 0, 0, 0, 0, 4, 0, 0, 0, 8]);
+        frame[3] += 11;
+        let err = v1::Account::from_frame(frame.into()).unwrap_err();
+        assert_eq!(err.kind(), &DecodeErrorKind::DuplicateKey { name: "field id".to_string() });
+        assert_eq!(err.path(), &[PathSegment::Field("id")]);
+        assert_eq!(err.offset(), Some(37));
+
+        let mut frame = old.into_frame().to_vec();
+        frame[6] = 1;
+        let err = v1::Account::from_frame(frame.clone().into()).unwrap_err();
+        assert_eq!(err.to_string(), "id: invalid 'wire type': expected 0 but got 1 at byte 6");
+
+        frame[6] = 0;
+        frame[10] = 2;
+        let err = v1::Account::from_frame(frame.into()).unwrap_err();
+        assert_eq!(err.path(), &[PathSegment::Field("id")]);
+        assert_eq!(err.offset(), Some(11));
+    }
+
+    #[test]
+    fn number_encodings() {
+        let value = Sample { sensor: 0x0102, port: 0x0304, serial: 300, offset: -2, value: 1.5, flags: [1, 2] };
+        let frame = value.into_frame();
+        assert_eq!(&frame[..], &[2, 1, 3, 4, 0xAC, 0x02, 3, 0, 0, 0xC0, 0x3F, 1, 2]);
+        assert_eq!(value.encoded_len(), frame.len());
+        assert_eq!(Sample::from_frame(frame).unwrap(), value);
+
+        let frame = Command::Reset.into_frame();
+        assert_eq!(&frame[..], &[2, 1]);
+        assert_eq!(Command::from_frame(frame).unwrap(), Command::Reset);
+
+        let value = Command::Seek(-1, 5);
+        let frame = value.into_frame();
+        assert_eq!(&frame[..], &[3, 1, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01, 5, 0, 0, 0]);
+        assert_eq!(value.encoded_len(), frame.len());
+        assert_eq!(Command::from_frame(frame).unwrap(), value);
+
+        // Varints must be minimal and fit the field
+        let err = Sample::from_frame(Bytes::from_static(&[2, 1, 3, 4, 0x80, 0x00])).unwrap_err();
+        assert_eq!(err.to_string(), "serial: invalid 'u64': overlong varint at byte 4");
+        let err = Command::from_frame(Bytes::from_static(&[3, 1, 0x80, 0x80, 0x02, 5, 0, 0, 0])).unwrap_err();
+        assert_eq!(err.to_string(), "Seek.0: invalid 'i16': 32768 is out of range at byte 2");
+        let err = utils::get_varint::<_, u32>(&mut &[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01][..], "count").unwrap_err();
+        assert_eq!(err.to_string(), "invalid 'count': 18446744073709551615 is out of range");
+        let value = utils::get_varint::<_, i8>(&mut &[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01][..], "count");
+        assert_eq!(value.unwrap(), -1);
+
+        let mut frame = BytesMut::new();
+        utils::put_varint_u64(&mut frame, u64::MAX);
+        assert_eq!(frame.len(), utils::varint_len_u64(u64::MAX));
+        assert_eq!(utils::get_varint_u64(&mut frame.freeze(), "varint").unwrap(), u64::MAX);
+        let err = utils::get_varint_u64(&mut &[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x02][..], "varint").unwrap_err();
+        assert_eq!(err.to_string(), "invalid 'varint': varint overflows a u64");
+    }
+
+    #[test]
+    fn length_prefixes() {
+        let value = Packet { name: "ab".to_string(), payload: vec![1, 2, 3], tags: BTreeSet::from([5]), ids: vec![9] };
+        let frame = value.into_frame();
+        assert_eq!(&frame[..], &[2, b'a', b'b', 0, 3, 1, 2, 3, 1, 0, 5, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 9]);
+        assert_eq!(value.encoded_len(), frame.len());
+        assert_eq!(Packet::from_frame_exact(frame).unwrap(), value);
+
+        let payload = vec![7; 200];
+        let value = PacketRef { name: "x", payload: &payload, parts: vec!["a", "bc"] };
+        let frame = value.into_frame();
+        assert_eq!(&frame[..5], &[1, b'x', 0xC8, 0x01, 7]);
+        assert_eq!(&frame[204..], &[0, 2, 0, 0, 0, 1, b'a', 0, 0, 0, 2, b'b', b'c']);
+        assert_eq!(value.encoded_len(), frame.len());
+        assert_eq!(PacketRef::from_frame_ref_exact(&frame).unwrap(), value);
+
+        let err = Packet::from_frame(Bytes::from_static(&[3, b'a', b'b'])).unwrap_err();
+        assert_eq!(err.path(), &[PathSegment::Field("name")]);
+        assert_eq!(err.to_string(), "name: expected 'String': needed 3 bytes but only 2 are left at byte 1");
+    }
+
+    #[test]
+    fn map_length_prefixes() {
+        let value = Directory {
+            owners: HashMap::from([(1, "a".to_string())]),
+            paths: BTreeMap::from([("x".to_string(), 2)]),
+            slots: vec![Some(3), None],
+        };
+        let frame = value.into_frame();
+        assert_eq!(&frame[..], &[1, 0, 1, 0, 0, 0, 1, b'a', 1, 0, 0, 0, 1, b'x', 0, 0, 0, 2, 0, 2, 1, 3, 0]);
+        assert_eq!(value.encoded_len(), frame.len());
+        assert_eq!(Directory::from_frame_exact(frame.clone()).unwrap(), value);
+
+        let slots = utils::get_option_array_with_prefix(&mut frame.slice(18..), "slots", LengthPrefix::U16, |src| utils::get_u8(src, "slot")).unwrap();
+        assert_eq!(slots, value.slots);
+        let mut encoded = BytesMut::new();
+        utils::put_option_array_with_prefix(&mut encoded, &value.slots, LengthPrefix::U16, |dst, slot| dst.put_u8(*slot));
+        assert_eq!(&encoded[..], &frame[18..]);
+
+        let value = DirectoryRef { paths: BTreeMap::from([("x", 2)]), names: HashSet::from(["y"]) };
+        let frame = value.into_frame();
+        assert_eq!(&frame[..], &[1, 0, 0, 0, 1, b'x', 2, 0, 1, 0, 0, 0, 1, b'y']);
+        assert_eq!(value.encoded_len(), frame.len());
+        assert_eq!(DirectoryRef::from_frame_ref_exact(&frame).unwrap(), value);
+
+        let err = Directory::from_frame(Bytes::from_static(&[2, 0, 1, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0])).unwrap_err();
+        assert_eq!(err.path(), &[PathSegment::Field("owners"), PathSegment::Index(1)]);
+        assert_eq!(err.kind(), &DecodeErrorKind::DuplicateKey { name: "HashMap < u16, String >".into() });
+
+        let value = Directory { owners: (0..256).map(|id| (id, String::new())).collect(), paths: BTreeMap::new(), slots: vec![] };
+        assert_eq!(value.try_into_frame().unwrap_err(), EncodeError::LengthOverflow { length: 256, prefix: LengthPrefix::U8 });
+    }
+
+    #[test]
+    fn put_helpers_with_prefix() {
+        let mut encoded = BytesMut::new();
+        utils::put_array_with_prefix(&mut encoded, &[1u8, 2], LengthPrefix::U8, |dst, value| dst.put_u8(*value));
+        utils::put_hashset_with_prefix(&mut encoded, &HashSet::from([3u8]), LengthPrefix::U16, |dst, value| dst.put_u8(*value));
+        utils::put_set_canonical_with_prefix(&mut encoded, HashSet::from([5u8, 4]).iter(), LengthPrefix::Varint, |dst, value| dst.put_u8(*value));
+        utils::put_map_canonical_with_prefix(&mut encoded, HashMap::from([(7u8, 8u8), (6, 9)]).iter(), LengthPrefix::U8, |dst, key| dst.put_u8(*key), |dst, value| dst.put_u8(*value));
+        assert_eq!(&encoded[..], &[2, 1, 2, 0, 1, 3, 2, 4, 5, 2, 6, 9, 7, 8]);
+    }
+
+    #[test]
+    fn length_prefix_overflow() {
+        let value = Packet { name: "a".repeat(256), payload: vec![], tags: BTreeSet::new(), ids: vec![] };
+        let err = value.try_into_frame().unwrap_err();
+        assert_eq!(err, EncodeError::LengthOverflow { length: 256, prefix: LengthPrefix::U8 });
+
+        let value = Packet { name: "a".repeat(255), payload: vec![0; 65_536], tags: BTreeSet::new(), ids: vec![] };
+        let err = value.try_into_frame().unwrap_err();
+        assert_eq!(err.to_string(), "length 65536 does not fit into a U16 length prefix");
+
+        let value = Packet { name: "a".repeat(255), payload: vec![0; 65_535], tags: BTreeSet::new(), ids: vec![] };
+        assert_eq!(value.try_into_frame().unwrap(), value.into_frame());
+
+        let nested = Some(vec![Packet { name: "a".repeat(256), payload: vec![], tags: BTreeSet::new(), ids: vec![] }]);
+        let mut frame = BytesMut::new();
+        let err = nested.try_extend_frame(&mut frame).unwrap_err();
+        assert_eq!(err, EncodeError::LengthOverflow { length: 256, prefix: LengthPrefix::U8 });
+    }
+
+    #[test]
+    #[should_panic(expected = "length 256 does not fit into a U8 length prefix")]
+    fn length_prefix_overflow_panics_without_try() {
+        let value = Packet { name: "a".repeat(256), payload: vec![], tags: BTreeSet::new(), ids: vec![] };
+        value.extend_frame(&mut BytesMut::new());
+    }
+}
@@ -1,3 +1,10 @@
+use std::borrow::Cow;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::num::{NonZeroU32, Wrapping};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::sync::Arc;
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap, HashSet, LinkedList, VecDeque};
+use std::hash::{BuildHasherDefault, DefaultHasher};
 use wire_framed::prelude::*;
 
 #[derive(Debug, Decoding, PartialEq, Eq)]
@@ -8,6 +15,87 @@ pub struct Foo {
     pub d: Vec<u8>,
 }
 
+#[derive(Debug, Decoding, PartialEq, Eq)]
+pub struct Outer {
+    pub id: u8,
+    pub foos: Vec<Foo>,
+}
+
+#[derive(Debug, Encoding, Decoding, PartialEq, Eq)]
+pub struct Borrowed<'a> {
+    pub id: u32,
+    pub name: &'a str,
+    pub data: &'a [u8],
+    pub tags: Vec<&'a str>,
+}
+
+#[derive(Debug, Encoding, Decoding)]
+pub struct BorrowedRegistry<'a> {
+    pub names: HashMap<u16, &'a str>,
+    pub ordered: BTreeMap<&'a str, u8>,
+    pub tags: HashSet<&'a str, BuildHasherDefault<DefaultHasher>>,
+    pub sorted: BTreeSet<u8>,
+    pub queue: VecDeque<&'a [u8]>,
+    pub list: LinkedList<u8>,
+    pub heap: BinaryHeap<u16>,
+}
+
+#[derive(Debug, Decoding, PartialEq, Eq)]
+pub struct Shared {
+    pub name: BytesStr,
+    pub data: Bytes,
+}
+
+#[derive(Debug, Decoding, PartialEq, Eq)]
+#[wire(strict)]
+pub struct Strict {
+    pub a: u16,
+}
+
+#[derive(Debug, Encoding, Decoding, PartialEq)]
+pub struct Telemetry {
+    pub temperature: f32,
+    pub pressure: f64,
+    pub counter: u128,
+    pub delta: i128,
+    pub unit: char,
+    pub index: usize,
+    pub offset: isize,
+}
+
+#[derive(Debug, Encoding, Decoding, PartialEq, Eq)]
+pub struct Registry {
+    pub names: HashMap<u16, String>,
+    pub ordered: BTreeMap<String, u8>,
+    pub tags: BTreeSet<u8>,
+    pub queue: VecDeque<u16>,
+    pub list: LinkedList<u8>,
+}
+
+#[derive(Debug, Encoding, Decoding, PartialEq, Eq)]
+pub struct Keyed {
+    pub hash: [u8; 32],
+    pub ports: [u16; 3],
+    pub names: [String; 2],
+}
+
+#[derive(Debug, Encoding, Decoding, PartialEq, Eq)]
+pub struct Wrapped<'a> {
+    pub shared: Arc<String>,
+    pub label: Cow<'a, str>,
+    pub id: NonZeroU32,
+    pub counter: Wrapping<u8>,
+    pub marker: std::marker::PhantomData<u64>,
+}
+
+#[derive(Debug, Encoding, Decoding, PartialEq, Eq)]
+pub struct Session {
+    pub timeout: Duration,
+    pub started: SystemTime,
+    pub peer: SocketAddr,
+    pub gateway: IpAddr,
+}
+
 #[cfg(test)]
 mod tests {
     use wire_framed::bytes::Bytes;
@@ -47,4 +135,408 @@ mod tests {
         assert_eq!(a, 1);
         assert_eq!(b, 2);
     }
+
+    #[test]
+    fn wide_tuples() {
+        assert_eq!(().into_frame().len(), 0);
+        assert_eq!(<()>::from_frame_exact(Bytes::new()).unwrap(), ());
+        assert_eq!(<(u16,)>::from_frame((9u16,).into_frame()).unwrap(), (9,));
+
+        type Wide = (u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, String);
+        let value: Wide = (0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, "last".to_string());
+        let frame = value.into_frame();
+        assert_eq!(frame.len(), 15 + 8);
+        let decoded = Wide::from_frame(frame.clone()).unwrap();
+        assert_eq!((decoded.0, decoded.14, decoded.15), (0, 14, "last".to_string()));
+
+        let Err(err) = Wide::from_frame(frame.slice(..20)) else { panic!("truncated tuple decoded") };
+        assert_eq!(err.path(), &[PathSegment::Index(15)]);
+        assert!(err.to_string().starts_with("[15]: "));
+    }
+
+    #[test]
+    fn error_path_and_offset() {
+        let frame = Bytes::from_static(&[
+            7, // id
+            0, 0, 0, 1, // foos
+            0, 0, 0, 1, // a
+            0, 2, // b
+            0, 0, 0, 2, // c
+            0xC3, 0x28, // c (invalid UTF-8)
+        ]);
+        let err = Outer::from_frame(frame).unwrap_err();
+
+        assert!(matches!(err.kind(), DecodeErrorKind::InvalidUtf8 { .. }));
+        assert_eq!(err.path(), &[PathSegment::Field("foos"), PathSegment::Index(0), PathSegment::Field("c")]);
+        assert_eq!(err.offset(), Some(15));
+
+        let err = std::io::Error::from(err);
+        assert!(err.to_string().starts_with("foos[0].c: 'string' is not a valid UTF-8 string"));
+
+        // The offset points at the string itself, whatever the width of its length prefix
+        let err = utils::get_bytes_str_with_prefix(&mut Bytes::from_static(&[0, 2, 0xC3, 0x28]), "name", LengthPrefix::U16).unwrap_err();
+        assert!(matches!(err.kind(), DecodeErrorKind::InvalidUtf8 { .. }));
+        assert_eq!(err.remaining(), Some(2));
+        let err = BytesStr::from_frame(Bytes::from_static(&[0, 0, 0, 1, 0xFF])).unwrap_err();
+        assert_eq!(err.remaining(), Some(1));
+    }
+
+    #[test]
+    fn limits() {
+        let frame = Bytes::from_static(&[
+            7, // id
+            0, 0, 0, 1, // foos
+            0, 0, 0, 1, // a
+            0, 2, // b
+            0, 0, 0, 5, // c
+            104, 101, 108, 108, 111, // c
+            0, 0, 0, 0, // d
+        ]);
+        assert!(Outer::from_frame_with_limits(frame.clone(), DecodeLimits::default()).is_ok());
+
+        let err = Outer::from_frame_with_limits(frame.clone(), DecodeLimits::new().with_max_depth(2)).unwrap_err();
+        assert_eq!(err.kind(), &DecodeErrorKind::DepthLimitExceeded { limit: 2 });
+        assert_eq!(err.path(), &[PathSegment::Field("foos"), PathSegment::Index(0)]);
+
+        let err = Outer::from_frame_with_limits(frame.clone(), DecodeLimits::new().with_max_string_length(4)).unwrap_err();
+        assert_eq!(err.kind(), &DecodeErrorKind::LengthLimitExceeded { name: "string".to_string(), length: 5, limit: 4 });
+
+        let bomb = Bytes::from_static(&[
+            7, // id
+            0xFF, 0xFF, 0xFF, 0xFF, // foos
+        ]);
+        let err = Outer::from_frame(bomb).unwrap_err();
+        assert!(matches!(err.kind(), DecodeErrorKind::LengthLimitExceeded { .. } | DecodeErrorKind::AllocationLimitExceeded { .. }));
+        assert_eq!(err.offset(), Some(5));
+
+        // The allocation budget covers the whole frame, also when `parse_frame` is called inside a scope
+        let strings = vec!["a".repeat(40), "b".repeat(40), "c".repeat(40)].into_frame();
+        let limits = DecodeLimits::new().with_max_allocation(150);
+        let requested = 3 * std::mem::size_of::<String>() + 80;
+        let err = Vec::<String>::from_frame_with_limits(strings.clone(), limits).unwrap_err();
+        assert_eq!(err.kind(), &DecodeErrorKind::AllocationLimitExceeded { requested, limit: 150 });
+        let err = wire_framed_core::limits::with_limits(limits, || Vec::<String>::parse_frame(&mut strings.clone())).unwrap_err();
+        assert_eq!(err.kind(), &DecodeErrorKind::AllocationLimitExceeded { requested, limit: 150 });
+    }
+
+    #[test]
+    fn borrowed() {
+        let value = Borrowed {
+            id: 1,
+            name: "hello",
+            data: &[1, 2, 3],
+            tags: vec!["a", "bc"],
+        };
+        let frame = value.into_frame();
+
+        let decoded = Borrowed::from_frame_ref(&frame).unwrap();
+        assert_eq!(decoded, value);
+        assert_eq!(decoded.name.as_ptr(), frame[8..].as_ptr());
+
+        let err = Borrowed::from_frame_ref(&frame[..10]).unwrap_err();
+        assert_eq!(err.path(), &[PathSegment::Field("name")]);
+        assert_eq!(err.offset(), Some(8));
+    }
+
+    #[test]
+    fn shared() {
+        let frame = Bytes::from_static(&[
+            0, 0, 0, 5, // name
+            104, 101, 108, 108, 111, // name
+            0, 0, 0, 2, // data
+            1, 2, // data
+        ]);
+        let shared = Shared::from_frame(frame.clone()).unwrap();
+
+        assert_eq!(shared.name, "hello");
+        assert_eq!(shared.name.as_ptr(), frame[4..].as_ptr());
+        assert_eq!(&shared.data[..], &[1, 2]);
+        assert_eq!(shared.data.as_ptr(), frame[13..].as_ptr());
+    }
+
+    #[test]
+    fn trailing_bytes() {
+        let frame = Bytes::from_static(&[
+            0, 1, // a
+            0, 2, // trailing
+        ]);
+
+        assert_eq!(u16::from_frame(frame.clone()).unwrap(), 1);
+        let err = u16::from_frame_exact(frame.clone()).unwrap_err();
+        assert_eq!(err.kind(), &DecodeErrorKind::TrailingBytes { count: 2 });
+
+        let err = Strict::from_frame(frame.clone()).unwrap_err();
+        assert_eq!(err.kind(), &DecodeErrorKind::TrailingBytes { count: 2 });
+        assert_eq!(err.offset(), Some(2));
+        assert_eq!(Strict::from_frame(frame.slice(..2)).unwrap(), Strict { a: 1 });
+
+        let err = Borrowed::from_frame_ref_exact(&[0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 9]).unwrap_err();
+        assert_eq!(err.kind(), &DecodeErrorKind::TrailingBytes { count: 1 });
+        assert_eq!(err.offset(), Some(16));
+    }
+
+    #[test]
+    fn wide_primitives() {
+        let value = Telemetry {
+            temperature: 21.5,
+            pressure: -1013.25,
+            counter: u128::MAX - 1,
+            delta: i128::MIN + 1,
+            unit: 'µ',
+            index: 42,
+            offset: -42,
+        };
+        let frame = value.into_frame();
+        assert_eq!(frame.len(), 4 + 8 + 16 + 16 + 4 + 8 + 8);
+        assert_eq!(&frame[..4], &21.5f32.to_be_bytes());
+        assert_eq!(&frame[56..], &(-42i64).to_be_bytes());
+        assert_eq!(Telemetry::from_frame(frame).unwrap(), value);
+
+        let err = char::from_frame(Bytes::from_static(&[0, 0, 0xD8, 0])).unwrap_err();
+        assert!(matches!(err.kind(), DecodeErrorKind::InvalidValue { .. }));
+    }
+
+    #[test]
+    fn collections() {
+        let value = Registry {
+            names: HashMap::from([(1, "one".to_string()), (2, "two".to_string())]),
+            ordered: BTreeMap::from([("b".to_string(), 2), ("a".to_string(), 1)]),
+            tags: BTreeSet::from([3, 1, 2]),
+            queue: VecDeque::from([5, 6]),
+            list: LinkedList::from([7]),
+        };
+        let frame = value.into_frame();
+        assert_eq!(frame.len(), value.encoded_len());
+        assert_eq!(Registry::from_frame(frame).unwrap(), value);
+
+        let heap = BinaryHeap::from([1u8, 9, 4]);
+        let decoded = BinaryHeap::<u8>::from_frame(heap.into_frame()).unwrap();
+        assert_eq!(decoded.into_sorted_vec(), vec![1, 4, 9]);
+
+        let frame = Bytes::from_static(&[
+            0, 0, 0, 2, // len
+            0, 1, 0, 0, 0, 1, b'a', // 1 => "a"
+            0, 1, 0, 0, 0, 1, b'b', // 1 => "b"
+        ]);
+        let err = HashMap::<u16, String>::from_frame(frame).unwrap_err();
+        assert_eq!(err.kind(), &DecodeErrorKind::DuplicateKey { name: "hashmap".into() });
+        assert_eq!(err.path(), &[PathSegment::Index(1)]);
+        assert_eq!(err.remaining(), Some(7));
+
+        let err = BTreeSet::<u8>::from_frame(Bytes::from_static(&[0, 0, 0, 2, 4, 4])).unwrap_err();
+        assert_eq!(err.kind(), &DecodeErrorKind::DuplicateKey { name: "btreeset".into() });
+    }
+
+    #[test]
+    fn borrowed_collections() {
+        let value = BorrowedRegistry {
+            names: HashMap::from([(1, "one"), (2, "two")]),
+            ordered: BTreeMap::from([("b", 2), ("a", 1)]),
+            tags: HashSet::from_iter(["x", "y"]),
+            sorted: BTreeSet::from([3, 1]),
+            queue: VecDeque::from([&[1, 2][..], &[]]),
+            list: LinkedList::from([7]),
+            heap: BinaryHeap::from([4, 2]),
+        };
+        let frame = value.into_frame();
+        assert_eq!(frame.len(), value.encoded_len());
+
+        let decoded = BorrowedRegistry::from_frame_ref(&frame).unwrap();
+        assert_eq!(decoded.names, value.names);
+        assert_eq!(decoded.ordered, value.ordered);
+        assert_eq!(decoded.tags, value.tags);
+        assert_eq!(decoded.sorted, value.sorted);
+        assert_eq!(decoded.queue, value.queue);
+        assert_eq!(decoded.list, value.list);
+        assert_eq!(decoded.heap.into_sorted_vec(), vec![2, 4]);
+
+        let hashed = HashSet::<u32, BuildHasherDefault<DefaultHasher>>::from_frame(Bytes::from_static(&[0, 0, 0, 1, 0, 0, 0, 9])).unwrap();
+        assert!(hashed.contains(&9));
+
+        let err = HashMap::<u16, &str>::from_frame_ref(&[0, 0, 0, 2, 0, 1, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0]).unwrap_err();
+        assert_eq!(err.kind(), &DecodeErrorKind::DuplicateKey { name: "hashmap".into() });
+    }
+
+    #[test]
+    fn canonical_encoding() {
+        let a: HashSet<u32> = (0..64).collect();
+        let b: HashSet<u32> = (0..64).rev().collect();
+        let frame = Canonical(a.clone()).into_frame();
+        assert_eq!(frame, Canonical(b).into_frame());
+        assert_eq!(&frame[..12], &[0, 0, 0, 64, 0, 0, 0, 0, 0, 0, 0, 1]);
+        assert_eq!(Canonical::<HashSet<u32>>::from_frame(frame).unwrap().into_inner(), a);
+
+        let map: HashMap<u16, u8> = (0..64).map(|i| (i, i as u8)).collect();
+        let frame = Canonical(map.clone()).into_frame();
+        assert_eq!(frame.len(), 4 + 64 * 3);
+        assert_eq!(&frame[4..10], &[0, 0, 0, 0, 1, 1]);
+        assert_eq!(HashMap::<u16, u8>::from_frame(frame).unwrap(), map);
+    }
+
+    #[test]
+    fn fixed_arrays() {
+        let value = Keyed {
+            hash: [7; 32],
+            ports: [80, 443, 8080],
+            names: ["a".to_string(), "bc".to_string()],
+        };
+        let frame = value.into_frame();
+        assert_eq!(frame.len(), 32 + 6 + 5 + 6);
+        assert_eq!(&frame[32..38], &[0, 80, 1, 187, 31, 144]);
+        assert_eq!(Keyed::from_frame(frame).unwrap(), value);
+
+        let err = <[u8; 4]>::from_frame(Bytes::from_static(&[1, 2, 3])).unwrap_err();
+        assert_eq!(err.kind(), &DecodeErrorKind::UnexpectedEof { name: "array".into(), needed: 4, available: 3 });
+        assert_eq!(<[u8; 2]>::from_frame_ref(&[1, 2]).unwrap(), [1, 2]);
+        assert_eq!(<[u8; 0]>::from_frame_exact(Bytes::new()).unwrap(), []);
+    }
+
+    #[test]
+    fn fixed_array_failure_drops_elements() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        static LIVE: AtomicUsize = AtomicUsize::new(0);
+
+        #[derive(Debug)]
+        struct Tracked;
+
+        impl Drop for Tracked {
+            fn drop(&mut self) {
+                LIVE.fetch_sub(1, Ordering::SeqCst);
+            }
+        }
+
+        impl FromFrame for Tracked {
+            type Error = DecodeError;
+
+            fn parse_frame(frame: &mut Bytes) -> Result<Self, Self::Error> {
+                wire_framed::utils::get_bool(frame, "tracked")?;
+                LIVE.fetch_add(1, Ordering::SeqCst);
+                Ok(Tracked)
+            }
+        }
+
+        let err = <[Tracked; 4]>::from_frame(Bytes::from_static(&[1, 1, 1])).unwrap_err();
+        assert_eq!(err.path(), &[PathSegment::Index(3)]);
+        assert_eq!(LIVE.load(Ordering::SeqCst), 0);
+
+        let arr = <[Tracked; 3]>::from_frame(Bytes::from_static(&[1, 1, 1])).unwrap();
+        assert_eq!(LIVE.load(Ordering::SeqCst), 3);
+        drop(arr);
+        assert_eq!(LIVE.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn wrappers() {
+        let value = Wrapped {
+            shared: Arc::new("config".to_string()),
+            label: Cow::Owned("label".to_string()),
+            id: NonZeroU32::new(7).unwrap(),
+            counter: Wrapping(255),
+            marker: std::marker::PhantomData,
+        };
+        let frame = value.into_frame();
+        assert_eq!(frame.len(), 10 + 9 + 4 + 1);
+
+        let decoded = Wrapped::from_frame_ref(&frame).unwrap();
+        assert!(matches!(decoded.label, Cow::Borrowed("label")));
+        assert_eq!(decoded, value);
+
+        let owned = Cow::<str>::from_frame(frame.slice(10..19)).unwrap();
+        assert!(matches!(owned, Cow::Owned(_)));
+
+        let err = NonZeroU32::from_frame(Bytes::from_static(&[0, 0, 0, 0])).unwrap_err();
+        assert_eq!(err.kind(), &DecodeErrorKind::InvalidValue { name: "NonZeroU32".into(), reason: "value must not be zero".into() });
+        assert_eq!(err.remaining(), Some(4));
+    }
+
+    #[test]
+    fn results() {
+        let reply: Result<u16, String> = Ok(7);
+        let frame = reply.into_frame();
+        assert_eq!(&frame[..], &[0, 0, 7]);
+        assert_eq!(Result::<u16, String>::from_frame(frame).unwrap(), reply);
+
+        let reply: Result<u16, String> = Err("nope".to_string());
+        let frame = reply.into_frame();
+        assert_eq!(frame.len(), reply.encoded_len());
+        assert_eq!(&frame[..1], &[1]);
+        assert_eq!(Result::<u16, String>::from_frame(frame).unwrap(), reply);
+
+        let err = Result::<u16, String>::from_frame(Bytes::from_static(&[2])).unwrap_err();
+        assert_eq!(err.kind(), &DecodeErrorKind::InvalidTag { name: "result".into(), tag: 2 });
+
+        let err = Result::<u16, String>::from_frame(Bytes::from_static(&[1, 0, 0])).unwrap_err();
+        assert_eq!(err.path(), &[PathSegment::Variant("Err")]);
+    }
+
+    #[test]
+    fn time_and_network() {
+        let value = Session {
+            timeout: Duration::new(30, 500),
+            started: UNIX_EPOCH + Duration::new(1_700_000_000, 42),
+            peer: "[::1]:8080".parse().unwrap(),
+            gateway: IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)),
+        };
+        let frame = value.into_frame();
+        assert_eq!(frame.len(), 12 + 12 + 27 + 5);
+        assert_eq!(&frame[51..], &[0, 10, 0, 0, 1]);
+        assert_eq!(Session::from_frame(frame).unwrap(), value);
+
+        let before = UNIX_EPOCH - Duration::from_nanos(1);
+        let frame = before.into_frame();
+        assert_eq!(&frame[..], &[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x3B, 0x9A, 0xC9, 0xFF]);
+        assert_eq!(SystemTime::from_frame(frame).unwrap(), before);
+
+        // The whole range of an `i64` of seconds is written without clamping
+        let latest = UNIX_EPOCH + Duration::new(i64::MAX as u64, 999_999_999);
+        let frame = latest.try_into_frame().unwrap();
+        assert_eq!(&frame[..], &[0x7F, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x3B, 0x9A, 0xC9, 0xFF]);
+        assert_eq!(SystemTime::from_frame(frame).unwrap(), latest);
+
+        let earliest = UNIX_EPOCH - Duration::from_secs(i64::MAX as u64) - Duration::from_secs(1);
+        let frame = earliest.try_into_frame().unwrap();
+        assert_eq!(&frame[..], &[0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(SystemTime::from_frame(frame).unwrap(), earliest);
+
+        let ip = Ipv6Addr::LOCALHOST;
+        assert_eq!(Ipv6Addr::from_frame_ref(&ip.into_frame()).unwrap(), ip);
+
+        let err = Duration::from_frame(Bytes::from_static(&[0, 0, 0, 0, 0, 0, 0, 1, 0x3B, 0x9A, 0xCA, 0x00])).unwrap_err();
+        assert!(matches!(err.kind(), DecodeErrorKind::InvalidValue { .. }));
+        assert_eq!(err.remaining(), Some(12));
+
+        let err = IpAddr::from_frame(Bytes::from_static(&[4, 127, 0, 0, 1])).unwrap_err();
+        assert_eq!(err.kind(), &DecodeErrorKind::InvalidTag { name: "IpAddr".into(), tag: 4 });
+    }
+
+    #[test]
+    fn byte_buffers() {
+        let blob: Vec<u8> = (0..=255).collect();
+        let frame = blob.into_frame();
+        assert_eq!(frame.len(), 4 + 256);
+        assert_eq!(frame, Bytes::from(blob.clone()).into_frame());
+        assert_eq!(frame, blob.as_slice().into_frame());
+        assert_eq!(Vec::<u8>::from_frame(frame.clone()).unwrap(), blob);
+        assert_eq!(Vec::<u8>::from_frame_ref(&frame).unwrap(), blob);
+
+        let mut queue = VecDeque::with_capacity(4);
+        queue.extend([9u8, 9, 1]);
+        queue.drain(..2);
+        queue.extend([2, 3]);
+        let queued = queue.into_frame();
+        assert_eq!(&queued[..], &[0, 0, 0, 3, 1, 2, 3]);
+        assert_eq!(queue.encoded_len(), queued.len());
+        assert_eq!(VecDeque::<u8>::from_frame(queued).unwrap(), queue);
+        assert_eq!(<[u8; 3]>::encoded_len_slice(&[[1, 2, 3], [4, 5, 6]]), 6);
+
+        let bytes = Bytes::from_frame(frame.clone()).unwrap();
+        assert_eq!(bytes.as_ptr(), frame[4..].as_ptr());
+
+        let err = Vec::<u8>::from_frame(frame.slice(..100)).unwrap_err();
+        assert_eq!(err.kind(), &DecodeErrorKind::UnexpectedEof { name: "array".into(), needed: 256, available: 96 });
+
+        let err = Vec::<u8>::from_frame_with_limits(frame, DecodeLimits::new().with_max_collection_length(255)).unwrap_err();
+        assert!(matches!(err.kind(), DecodeErrorKind::LengthLimitExceeded { length: 256, limit: 255, .. }));
+    }
 }
\ No newline at end of file
@@ -7,6 +7,59 @@ pub enum Test {
     Baz(String),
 }
 
+#[derive(Debug, Encoding, Decoding, Clone, PartialEq, Eq)]
+pub enum Expr {
+    Literal(i32),
+    Neg(Box<Expr>),
+    Add(Box<Expr>, Box<Expr>),
+}
+
+#[derive(Debug, Encoding, Decoding, Clone, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Status {
+    Created = 10,
+    Running(u8),
+    Done { code: i32 } = 20,
+    Failed,
+}
+
+#[derive(Debug, Encoding, Decoding, Clone, Copy, PartialEq, Eq)]
+#[wire(tag = u32)]
+pub enum Opcode {
+    Nop,
+    Halt = 70_000,
+}
+
+#[derive(Debug, Encoding, Decoding, Clone, PartialEq, Eq)]
+pub enum Event {
+    Joined(u32),
+    Left(u32),
+    #[wire(other)]
+    Unknown(u8, Bytes),
+}
+
+#[derive(Debug, Encoding, Decoding, Clone, PartialEq, Eq)]
+#[wire(tag = u16)]
+pub enum Command<'a> {
+    Start { id: u8 },
+    #[wire(other)]
+    Unsupported { tag: u16, payload: &'a [u8] },
+}
+
+#[derive(Debug, Decoding, Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+    Low,
+    High,
+    #[wire(other)]
+    Other,
+}
+
+#[derive(Debug, Encoding, Decoding, Clone, PartialEq, Eq)]
+pub struct Pair {
+    pub event: Event,
+    pub id: u16,
+}
+
 #[cfg(test)]
 mod tests {
     use wire_framed::bytes::Bytes;
@@ -33,4 +86,112 @@ mod tests {
         let foo = Test::from_frame(frame);
         println!("{:?}", foo);
     }
+
+    #[test]
+    fn invalid_kind() {
+        let frame = Bytes::from_static(&[
+            3, // unknown
+        ]);
+        let err = Test::from_frame(frame).unwrap_err();
+
+        assert_eq!(err.kind(), &DecodeErrorKind::InvalidTag { name: "Test kind".to_string(), tag: 3 });
+        assert_eq!(err.offset(), Some(0));
+
+        let frame = Bytes::from_static(&[
+            1, // Bar
+            0, // 0
+        ]);
+        let err = Test::from_frame(frame).unwrap_err();
+        assert_eq!(err.path(), &[PathSegment::Variant("Bar"), PathSegment::Field("0")]);
+        assert_eq!(err.offset(), Some(1));
+    }
+
+    #[test]
+    fn recursive_boxed() {
+        let expr = Expr::Add(Box::new(Expr::Literal(1)), Box::new(Expr::Neg(Box::new(Expr::Literal(2)))));
+        let frame = expr.into_frame();
+        assert_eq!(&frame[..], &[2, 0, 0, 0, 0, 1, 1, 0, 0, 0, 0, 2]);
+        assert_eq!(Expr::from_frame(frame).unwrap(), expr);
+
+        let mut nested = vec![1; 200];
+        nested.push(0);
+        nested.extend_from_slice(&[0, 0, 0, 0]);
+        let err = Expr::from_frame(nested.into()).unwrap_err();
+        assert!(matches!(err.kind(), DecodeErrorKind::DepthLimitExceeded { .. }));
+    }
+
+    #[test]
+    fn explicit_discriminants() {
+        let values = [Status::Created, Status::Running(3), Status::Done { code: -1 }, Status::Failed];
+        let tags = values.iter().map(|value| value.into_frame()[0]).collect::<Vec<_>>();
+        assert_eq!(tags, [10, 11, 20, 21]);
+
+        for value in values {
+            assert_eq!(Status::from_frame(value.into_frame()).unwrap(), value);
+        }
+
+        let err = Status::from_frame(Bytes::from_static(&[0])).unwrap_err();
+        assert_eq!(err.kind(), &DecodeErrorKind::InvalidTag { name: "Status kind".into(), tag: 0 });
+    }
+
+    #[test]
+    fn wide_tags() {
+        let frame = Opcode::Halt.into_frame();
+        assert_eq!(&frame[..], &[0, 1, 0x11, 0x70]);
+        assert_eq!(Opcode::Halt.encoded_len(), 4);
+        assert_eq!(Opcode::from_frame(frame).unwrap(), Opcode::Halt);
+        assert_eq!(Opcode::from_frame(Bytes::from_static(&[0, 0, 0, 0])).unwrap(), Opcode::Nop);
+
+        let err = Opcode::from_frame(Bytes::from_static(&[0, 0, 0, 1])).unwrap_err();
+        assert_eq!(err.kind(), &DecodeErrorKind::InvalidTag { name: "Opcode kind".into(), tag: 1 });
+        assert_eq!(err.offset(), Some(0));
+    }
+
+    #[test]
+    fn fallback_variant() {
+        let frame = Bytes::from_static(&[9, 0, 0, 0, 3, 1, 2, 3]);
+        let event = Event::from_frame(frame.clone()).unwrap();
+        assert_eq!(event, Event::Unknown(9, Bytes::from_static(&[1, 2, 3])));
+        assert_eq!(event.into_frame(), frame);
+        assert_eq!(event.encoded_len(), 8);
+        assert_eq!(&Event::Left(5).into_frame()[..], &[1, 0, 0, 0, 4, 0, 0, 0, 5]);
+        assert_eq!(Event::Left(5).encoded_len(), 9);
+        assert_eq!(Event::from_frame(Event::Left(5).into_frame()).unwrap(), Event::Left(5));
+
+        let frame = [0, 4, 0, 0, 0, 2, 0xAA, 0xBB];
+        let command = Command::from_frame_ref(&frame).unwrap();
+        assert_eq!(command, Command::Unsupported { tag: 4, payload: &[0xAA, 0xBB] });
+        assert_eq!(&command.into_frame()[..], &frame);
+        assert_eq!(Command::from_frame_ref(&[0, 0, 0, 0, 0, 1, 7]).unwrap(), Command::Start { id: 7 });
+
+        assert_eq!(Level::from_frame_exact(Bytes::from_static(&[1, 0, 0, 0, 0])).unwrap(), Level::High);
+        assert_eq!(Level::from_frame_exact(Bytes::from_static(&[2, 0, 0, 0, 0])).unwrap(), Level::Other);
+        assert_eq!(Level::from_frame_exact(Bytes::from_static(&[7, 0, 0, 0, 1, 0])).unwrap(), Level::Other);
+
+        // The fallback only takes the bytes of its own variant
+        let err = Level::from_frame_exact(Bytes::from_static(&[7, 0, 0, 0, 0, 1])).unwrap_err();
+        assert_eq!(err.kind(), &DecodeErrorKind::TrailingBytes { count: 1 });
+
+        let err = Event::from_frame(Bytes::from_static(&[0, 0, 0, 0, 2, 0, 0])).unwrap_err();
+        assert_eq!(err.path(), &[PathSegment::Variant("Joined"), PathSegment::Field("0")]);
+        assert_eq!(err.offset(), Some(5));
+    }
+
+    #[test]
+    fn nested_fallback_variant() {
+        let pair = Pair { event: Event::Unknown(9, Bytes::from_static(&[1, 2])), id: 7 };
+        let frame = pair.into_frame();
+        assert_eq!(&frame[..], &[9, 0, 0, 0, 2, 1, 2, 0, 7]);
+        assert_eq!(Pair::from_frame_exact(frame).unwrap(), pair);
+
+        let events = vec![Event::Unknown(9, Bytes::from_static(&[1, 2])), Event::Joined(1)];
+        assert_eq!(Vec::<Event>::from_frame_exact(events.into_frame()).unwrap(), events);
+
+        let value = (Some(Event::Unknown(3, Bytes::new())), 5u16);
+        assert_eq!(<(Option<Event>, u16)>::from_frame_exact(value.into_frame()).unwrap(), value);
+
+        // Fields a newer writer appended to a known variant are skipped
+        let frame = Bytes::from_static(&[1, 0, 0, 0, 5, 0, 0, 0, 5, 9, 0, 2]);
+        assert_eq!(Pair::from_frame_exact(frame).unwrap(), Pair { event: Event::Left(5), id: 2 });
+    }
 }
\ No newline at end of file
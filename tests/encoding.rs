@@ -30,6 +30,7 @@ mod tests {
         };
 
         let frame = foo.into_frame();
+        assert_eq!(foo.encoded_len(), frame.len());
         let result = Bytes::from_static(&[
             0, 0, 0, 1, // a
             0, 2, // b
@@ -41,4 +42,12 @@ mod tests {
 
         assert_eq!(frame, result);
     }
+
+    #[test]
+    fn encoded_len() {
+        assert_eq!(Baz(7).encoded_len(), 4);
+        assert_eq!(Bar.encoded_len(), 0);
+        assert_eq!(Some((1u8, "ab".to_string())).encoded_len(), 8);
+        assert_eq!(vec![Some(1u16), None].encoded_len(), 8);
+    }
 }
\ No newline at end of file
@@ -5,6 +5,8 @@ pub enum Test {
     Foo(u32),
     Bar(u16),
     Baz(String),
+    Qux { a: u8, b: Vec<u16> },
+    Empty,
 }
 
 #[cfg(test)]
@@ -17,7 +19,8 @@ mod tests {
     fn test1() {
         let foo = Test::Baz("John".to_string());
 
-        assert_eq!(foo.size_hint(), 8);
+        assert_eq!(foo.size_hint(), 9);
+        assert_eq!(foo.encoded_len(), 9);
 
         let frame = foo.into_frame();
         let result = Bytes::from_static(&[
@@ -28,4 +31,18 @@ mod tests {
 
         assert_eq!(frame, result);
     }
+
+    #[test]
+    fn encoded_len() {
+        let values = [
+            Test::Foo(1),
+            Test::Bar(2),
+            Test::Qux { a: 3, b: vec![4, 5] },
+            Test::Empty,
+        ];
+
+        for value in values {
+            assert_eq!(value.encoded_len(), value.into_frame().len());
+        }
+    }
 }
\ No newline at end of file